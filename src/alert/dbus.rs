@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::OnceCell;
+use zbus::zvariant::Value;
+use zbus::Connection;
+
+use super::{Notification, NotificationSink};
+use crate::config::utils::app_name;
+use crate::logging::{debug, warn};
+
+/// Sends notifications to the desktop's `org.freedesktop.Notifications`
+/// service on the session bus, connecting on first use. Without a session
+/// bus notifications are dropped.
+#[derive(Default)]
+pub struct DbusSink {
+    connection: Arc<OnceCell<Option<Connection>>>,
+}
+
+impl NotificationSink for DbusSink {
+    fn send(&self, notification: Notification) {
+        let connection = self.connection.clone();
+        tokio::spawn(async move {
+            let connection = connection
+                .get_or_init(|| async {
+                    Connection::session()
+                        .await
+                        .inspect_err(|e| {
+                            warn!("No D-Bus session bus, not sending notifications: {}", e)
+                        })
+                        .ok()
+                })
+                .await;
+            let Some(connection) = connection else {
+                return;
+            };
+
+            match notify(connection, &notification).await {
+                Ok(id) => debug!("Sent notification {}: {}", id, notification.summary),
+                Err(e) => warn!("Failed to send notification: {}", e),
+            }
+        });
+    }
+}
+
+async fn notify(connection: &Connection, notification: &Notification) -> zbus::Result<u32> {
+    let actions: Vec<&str> = Vec::new();
+    let hints: HashMap<&str, Value> = HashMap::new();
+    let reply = connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            // App name, id to replace, icon, summary, body, actions, hints
+            // and timeout, -1 for the server's default.
+            &(
+                app_name(),
+                0u32,
+                "",
+                notification.summary.as_str(),
+                notification.body.as_str(),
+                actions,
+                hints,
+                -1i32,
+            ),
+        )
+        .await?;
+    reply.body().deserialize()
+}
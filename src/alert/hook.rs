@@ -0,0 +1,277 @@
+use std::fmt;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command as SysCommand;
+use tokio::time::timeout;
+
+use crate::command::{shell_program, CommandOutput, OutputView};
+use crate::logging::{debug, warn};
+
+/// Hooks still running after this are killed, so a stuck one can't pile up.
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Output passed in an environment variable is cut to this many bytes, as
+/// the OS limits their size. The whole new output is on stdin.
+const ENV_OUTPUT_LIMIT: usize = 64 * 1024;
+
+/// Commands run when a pane's runs change, start failing or recover.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pub on_change: Option<String>,
+    pub on_fail: Option<String>,
+    pub on_recover: Option<String>,
+}
+
+/// What set a hook off.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HookEvent {
+    /// The output differs from the previous run's.
+    Change,
+    /// The command failed after a run that didn't, or on its first run.
+    Fail,
+    /// The command succeeded after a failed run.
+    Recover,
+    /// An alert rule fired, shown as written.
+    Alert(String),
+}
+
+/// The run a hook is told about.
+pub struct HookRun<'a> {
+    pub pane_id: usize,
+    pub exec: &'a str,
+    /// The pane's shell, which the hook runs with too.
+    pub shell: Option<&'a str>,
+    pub event: HookEvent,
+    pub previous: Option<&'a CommandOutput>,
+    pub out: &'a CommandOutput,
+}
+
+fn succeeded(out: &CommandOutput) -> bool {
+    out.exit_status == Some(0)
+}
+
+impl HookEvent {
+    /// The events of `out`, the run after `previous`.
+    pub fn of_run(previous: Option<&CommandOutput>, out: &CommandOutput) -> Vec<HookEvent> {
+        let mut events = Vec::new();
+        if previous.is_some_and(|prev| !prev.same_output(out)) {
+            events.push(HookEvent::Change);
+        }
+        match (previous.map(succeeded), succeeded(out)) {
+            (None | Some(true), false) => events.push(HookEvent::Fail),
+            (Some(false), true) => events.push(HookEvent::Recover),
+            _ => {}
+        }
+        events
+    }
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        *self == Hooks::default()
+    }
+
+    /// The hook for `event`, if one is set.
+    pub fn get(&self, event: &HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::Change => self.on_change.as_deref(),
+            HookEvent::Fail => self.on_fail.as_deref(),
+            HookEvent::Recover => self.on_recover.as_deref(),
+            HookEvent::Alert(_) => None,
+        }
+    }
+
+    /// Sets a hook from `EVENT = COMMAND`, e.g. `fail = notify-send failed`,
+    /// or removes it when the command is left out.
+    pub fn set(&mut self, spec: &str) -> Result<(), String> {
+        let (event, hook) = spec.split_once('=').unwrap_or((spec, ""));
+        let slot = match event.trim() {
+            "change" => &mut self.on_change,
+            "fail" => &mut self.on_fail,
+            "recover" => &mut self.on_recover,
+            other => return Err(format!("'{}' is not one of change, fail or recover", other)),
+        };
+        let hook = hook.trim();
+        *slot = (!hook.is_empty()).then(|| hook.to_string());
+        Ok(())
+    }
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HookEvent::Change => write!(f, "change"),
+            HookEvent::Fail => write!(f, "fail"),
+            HookEvent::Recover => write!(f, "recover"),
+            HookEvent::Alert(_) => write!(f, "alert"),
+        }
+    }
+}
+
+impl fmt::Display for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hooks: Vec<String> = [
+            ("change", &self.on_change),
+            ("fail", &self.on_fail),
+            ("recover", &self.on_recover),
+        ]
+        .into_iter()
+        .filter_map(|(event, hook)| Some(format!("{} = {}", event, hook.as_ref()?)))
+        .collect();
+
+        if hooks.is_empty() {
+            write!(f, "N/A")
+        } else {
+            write!(f, "{}", hooks.join("; "))
+        }
+    }
+}
+
+/// The start of `text`, at most [`ENV_OUTPUT_LIMIT`] bytes of it.
+fn env_output(text: &str) -> String {
+    let mut end = text.len().min(ENV_OUTPUT_LIMIT);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text[..end].to_string()
+}
+
+impl HookRun<'_> {
+    fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![
+            ("PANE_ID", self.pane_id.to_string()),
+            ("PANE_COMMAND", self.exec.to_string()),
+            ("PANE_EVENT", self.event.to_string()),
+            (
+                "PANE_EXIT_STATUS",
+                self.out
+                    .exit_status
+                    .map_or(String::new(), |c| c.to_string()),
+            ),
+            ("PANE_OUTPUT", env_output(&self.out.text(OutputView::Both))),
+            (
+                "PANE_PREVIOUS_OUTPUT",
+                self.previous.map_or(String::new(), |prev| {
+                    env_output(&prev.text(OutputView::Both))
+                }),
+            ),
+        ];
+        if let HookEvent::Alert(rule) = &self.event {
+            env.push(("PANE_ALERT", rule.clone()));
+        }
+        env
+    }
+}
+
+/// Runs `hook` with the pane's shell in the background, telling it about
+/// `run` through `PANE_*` variables and writing the new output to its stdin.
+/// A hook that fails or can't be started is logged.
+pub fn run_hook(hook: &str, run: HookRun) {
+    let hook = hook.to_string();
+    let shell = shell_program(run.shell);
+    let env = run.env();
+    let stdin = run.out.text(OutputView::Both).into_owned();
+
+    tokio::spawn(async move {
+        // The hook leads its own process group, so what it started is killed
+        // with it on timeout.
+        let mut pgid = None;
+        let run = async {
+            let mut child = SysCommand::new(&shell)
+                .arg("-c")
+                .arg(&hook)
+                .envs(env)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .process_group(0)
+                .kill_on_drop(true)
+                .spawn()?;
+            pgid = child.id().map(|pid| pid as libc::pid_t);
+
+            if let Some(mut pipe) = child.stdin.take() {
+                // A hook that doesn't read its input may exit before taking it all.
+                if let Err(e) = pipe.write_all(stdin.as_bytes()).await {
+                    debug!("Hook '{}' didn't read its input: {}", hook, e);
+                }
+            }
+            child.wait_with_output().await
+        };
+
+        match timeout(HOOK_TIMEOUT, run).await {
+            Ok(Ok(output)) if output.status.success() => {
+                debug!("Hook '{}' finished", hook);
+            }
+            Ok(Ok(output)) => warn!(
+                "Hook '{}' failed with {}: {}",
+                hook,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Ok(Err(e)) => warn!("Failed to run hook '{}': {}", hook, e),
+            Err(_) => {
+                if let Some(pgid) = pgid {
+                    unsafe { libc::killpg(pgid, libc::SIGKILL) };
+                }
+                warn!("Hook '{}' timed out after {:?}", hook, HOOK_TIMEOUT);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_output(stdout: &str, exit_status: i32) -> CommandOutput {
+        CommandOutput {
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            order: Vec::new(),
+            time: chrono::Local::now().naive_local(),
+            exit_status: Some(exit_status),
+            duration: Duration::from_millis(1),
+            timed_out: false,
+            attempts: 1,
+            usage: None,
+            repeats: 1,
+            first_seen: None,
+        }
+    }
+
+    #[test]
+    fn test_events_of_run() {
+        let ok = mock_output("a\n", 0);
+        let failed = mock_output("a\n", 1);
+
+        assert_eq!(HookEvent::of_run(None, &ok), vec![]);
+        assert_eq!(HookEvent::of_run(None, &failed), vec![HookEvent::Fail]);
+        assert_eq!(HookEvent::of_run(Some(&ok), &failed), vec![HookEvent::Fail]);
+        // Failing again isn't news.
+        assert_eq!(HookEvent::of_run(Some(&failed), &failed), vec![]);
+        assert_eq!(
+            HookEvent::of_run(Some(&failed), &mock_output("b\n", 0)),
+            vec![HookEvent::Change, HookEvent::Recover]
+        );
+    }
+
+    #[test]
+    fn test_sets_hooks_by_event() {
+        let mut hooks = Hooks::default();
+        hooks.set("fail = notify-send \"a = b\"").unwrap();
+        hooks.set("change=echo changed").unwrap();
+        assert_eq!(
+            hooks.to_string(),
+            "change = echo changed; fail = notify-send \"a = b\""
+        );
+
+        hooks.set("change =").unwrap();
+        hooks.set("fail").unwrap();
+        assert!(hooks.is_empty());
+        assert!(hooks.set("exit = true").is_err());
+    }
+}
@@ -0,0 +1,9 @@
+#[cfg(all(feature = "notify", target_os = "linux"))]
+mod dbus;
+mod hook;
+mod notify;
+mod rule;
+
+pub use hook::{run_hook, HookEvent, HookRun, Hooks};
+pub use notify::{desktop_sink, diff_summary, Notification, NotificationSink};
+pub use rule::{AlertAction, AlertRule};
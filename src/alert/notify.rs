@@ -0,0 +1,84 @@
+use similar::{ChangeTag, TextDiff};
+
+#[cfg(not(all(feature = "notify", target_os = "linux")))]
+use crate::logging::warn;
+
+/// Added lines quoted in a notification are cut to this many characters.
+const QUOTE_LIMIT: usize = 80;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+}
+
+/// Where desktop notifications go, a stand-in in tests.
+pub trait NotificationSink: Send + Sync {
+    /// Sends `notification` in the background. It's dropped, and the reason
+    /// logged, when it can't be shown.
+    fn send(&self, notification: Notification);
+}
+
+/// The sink desktop notifications go to: the session bus when built with the
+/// `notify` feature on Linux, otherwise the log.
+pub fn desktop_sink() -> Box<dyn NotificationSink> {
+    #[cfg(all(feature = "notify", target_os = "linux"))]
+    return Box::new(super::dbus::DbusSink::default());
+
+    #[cfg(not(all(feature = "notify", target_os = "linux")))]
+    return Box::new(LogSink);
+}
+
+/// Logs notifications in builds without desktop notification support.
+#[cfg(not(all(feature = "notify", target_os = "linux")))]
+struct LogSink;
+
+#[cfg(not(all(feature = "notify", target_os = "linux")))]
+impl NotificationSink for LogSink {
+    fn send(&self, notification: Notification) {
+        warn!(
+            "Built without desktop notifications, not sending: {}",
+            notification.summary
+        );
+    }
+}
+
+/// How many lines `current` adds and removes since `previous`, with the first
+/// line it adds, e.g. `+2 -1 lines: disk full`.
+pub fn diff_summary(previous: &str, current: &str) -> String {
+    let diff = TextDiff::from_lines(previous, current);
+    let (mut added, mut removed, mut first_added) = (0, 0, None);
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => {
+                added += 1;
+                first_added.get_or_insert_with(|| change.value().trim().to_string());
+            }
+            ChangeTag::Delete => removed += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+
+    let counts = format!("+{} -{} lines", added, removed);
+    match first_added.filter(|line| !line.is_empty()) {
+        Some(line) if line.chars().count() > QUOTE_LIMIT => {
+            let cut: String = line.chars().take(QUOTE_LIMIT).collect();
+            format!("{}: {}…", counts, cut)
+        }
+        Some(line) => format!("{}: {}", counts, line),
+        None => counts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_summary_counts_lines() {
+        assert_eq!(diff_summary("a\nb\n", "a\nb\n"), "+0 -0 lines");
+        assert_eq!(diff_summary("a\nb\n", "a\nc\nd\n"), "+2 -1 lines: c");
+        let long = "x".repeat(100);
+        assert!(diff_summary("", &long).ends_with(&format!("{}…", "x".repeat(80))));
+    }
+}
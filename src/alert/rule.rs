@@ -0,0 +1,265 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::command::{CommandOutput, OutputView};
+use crate::config::utils::parse_duration;
+
+/// Something to watch a pane's runs for, and what to do once it happens,
+/// written like `value > 90 => beep, flash`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AlertRule {
+    pub condition: Condition,
+    pub actions: Vec<AlertAction>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Condition {
+    /// The output matches, e.g. `match /ERROR/`.
+    Matches(Regex),
+    /// The output stops matching, e.g. `!match /healthy/`.
+    NotMatches(Regex),
+    /// The pane's extracted value goes above a threshold, e.g. `value > 90`.
+    Above(f64),
+    /// The pane's extracted value goes below a threshold, e.g. `value < 10`.
+    Below(f64),
+    /// The output differs from the previous run's.
+    Changed,
+    /// The run took longer than this, e.g. `took > 2s`.
+    SlowerThan(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertAction {
+    Beep,
+    /// Flash the pane's border.
+    Flash,
+    /// Show a banner in the status line.
+    Banner,
+    /// Send a desktop notification.
+    Notify,
+    /// Run a command with the pane's shell, e.g. `run notify-send "disk full"`.
+    Run(String),
+}
+
+impl Condition {
+    /// Whether the condition holds for `out`, which follows `previous`.
+    /// `value` is the number the pane extracts from `out`, if any.
+    pub fn holds(
+        &self,
+        previous: Option<&CommandOutput>,
+        out: &CommandOutput,
+        value: Option<f64>,
+    ) -> bool {
+        match self {
+            Condition::Matches(re) => re.is_match(&out.text(OutputView::Both)),
+            Condition::NotMatches(re) => !re.is_match(&out.text(OutputView::Both)),
+            Condition::Above(threshold) => value.is_some_and(|v| v > *threshold),
+            Condition::Below(threshold) => value.is_some_and(|v| v < *threshold),
+            Condition::Changed => previous.is_some_and(|prev| !prev.same_output(out)),
+            Condition::SlowerThan(limit) => out.duration > *limit,
+        }
+    }
+
+    /// Whether the condition fires on a run where it `holds`, given whether
+    /// it held on the run before, `None` before the first run.
+    ///
+    /// Conditions fire when they start to hold rather than on every run, so
+    /// a value staying above its threshold alerts once. A change is news
+    /// every time, and output can only stop matching once it has matched.
+    pub fn fires(&self, holds: bool, held: Option<bool>) -> bool {
+        match (self, held) {
+            (_, _) if !holds => false,
+            (Condition::Changed, _) => true,
+            (Condition::NotMatches(_), None) => false,
+            (_, held) => held != Some(true),
+        }
+    }
+}
+
+fn parse_regex(s: &str) -> Result<Regex, String> {
+    let pattern = s
+        .strip_prefix('/')
+        .and_then(|p| p.strip_suffix('/'))
+        .filter(|p| !p.is_empty())
+        .unwrap_or(s);
+    Regex::new(pattern).map_err(|e| format!("invalid regex: {}", e))
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let number = |n: &str| {
+            n.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("'{}' is not a number", n.trim()))
+        };
+
+        if let Some(pattern) = s.strip_prefix("!match ") {
+            return parse_regex(pattern.trim()).map(Condition::NotMatches);
+        }
+        if let Some(pattern) = s.strip_prefix("match ") {
+            return parse_regex(pattern.trim()).map(Condition::Matches);
+        }
+        if let Some(threshold) = s.strip_prefix("value >") {
+            return number(threshold).map(Condition::Above);
+        }
+        if let Some(threshold) = s.strip_prefix("value <") {
+            return number(threshold).map(Condition::Below);
+        }
+        if let Some(limit) = s.strip_prefix("took >") {
+            return parse_duration(limit.trim()).map(Condition::SlowerThan);
+        }
+        if s == "changed" {
+            return Ok(Condition::Changed);
+        }
+
+        Err(format!(
+            "'{}' is not one of match /RE/, !match /RE/, value > N, value < N, changed or took > DURATION",
+            s
+        ))
+    }
+}
+
+impl FromStr for AlertRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (condition, mut rest) = s
+            .split_once("=>")
+            .ok_or_else(|| "expected a rule like 'CONDITION => ACTION, ...'".to_string())?;
+
+        let mut actions = Vec::new();
+        loop {
+            let trimmed = rest.trim_start();
+            // A command can contain commas, so it takes the rest of the rule.
+            if let Some(hook) = trimmed.strip_prefix("run ") {
+                actions.push(AlertAction::Run(hook.trim().to_string()));
+                break;
+            }
+
+            let (action, after) = match trimmed.split_once(',') {
+                Some((action, after)) => (action, Some(after)),
+                None => (trimmed, None),
+            };
+            actions.push(match action.trim() {
+                "beep" => AlertAction::Beep,
+                "flash" => AlertAction::Flash,
+                "banner" => AlertAction::Banner,
+                "notify" => AlertAction::Notify,
+                other => {
+                    return Err(format!(
+                        "'{}' is not one of beep, flash, banner, notify or run COMMAND",
+                        other
+                    ))
+                }
+            });
+            match after {
+                Some(after) => rest = after,
+                None => break,
+            }
+        }
+
+        Ok(AlertRule {
+            condition: condition.parse()?,
+            actions,
+        })
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Condition::Matches(re) => write!(f, "match /{}/", re.as_str()),
+            Condition::NotMatches(re) => write!(f, "!match /{}/", re.as_str()),
+            Condition::Above(threshold) => write!(f, "value > {}", threshold),
+            Condition::Below(threshold) => write!(f, "value < {}", threshold),
+            Condition::Changed => write!(f, "changed"),
+            Condition::SlowerThan(limit) => {
+                write!(f, "took > {}", humantime::format_duration(*limit))
+            }
+        }
+    }
+}
+
+impl fmt::Display for AlertAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlertAction::Beep => write!(f, "beep"),
+            AlertAction::Flash => write!(f, "flash"),
+            AlertAction::Banner => write!(f, "banner"),
+            AlertAction::Notify => write!(f, "notify"),
+            AlertAction::Run(hook) => write!(f, "run {}", hook),
+        }
+    }
+}
+
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let actions: Vec<String> = self.actions.iter().map(|a| a.to_string()).collect();
+        write!(f, "{} => {}", self.condition, actions.join(", "))
+    }
+}
+
+impl TryFrom<String> for AlertRule {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<AlertRule> for String {
+    fn from(rule: AlertRule) -> Self {
+        rule.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_and_shows_rules() {
+        for source in [
+            "value > 90 => beep, flash, notify",
+            "!match /healthy/ => banner, run notify-send \"down, again\"",
+            "took > 2s => banner",
+            "changed => run echo changed",
+        ] {
+            assert_eq!(source.parse::<AlertRule>().unwrap().to_string(), source);
+        }
+
+        let rule: AlertRule = "match ERROR => run mail -s a,b me".parse().unwrap();
+        assert_eq!(
+            rule.actions,
+            vec![AlertAction::Run("mail -s a,b me".to_string())]
+        );
+
+        assert!("value > 90".parse::<AlertRule>().is_err());
+        assert!("value > x => beep".parse::<AlertRule>().is_err());
+        assert!("changed => shout".parse::<AlertRule>().is_err());
+    }
+
+    #[test]
+    fn test_fires_when_condition_starts_to_hold() {
+        let above = Condition::Above(90.0);
+        assert!(above.fires(true, None));
+        assert!(!above.fires(true, Some(true)));
+        assert!(above.fires(true, Some(false)));
+        assert!(!above.fires(false, Some(false)));
+
+        // Output has to have matched before it can stop matching.
+        let stopped = Condition::NotMatches(Regex::new("ok").unwrap());
+        assert!(!stopped.fires(true, None));
+        assert!(stopped.fires(true, Some(false)));
+
+        assert!(Condition::Changed.fires(true, Some(true)));
+    }
+}
@@ -1,24 +1,40 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::io::{self};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crossterm::event::EventStream;
 use futures::{FutureExt, StreamExt};
 use ratatui::layout::Rect;
 use ratatui::prelude::Backend;
 use ratatui::Terminal;
+use similar::TextDiff;
 use tokio::sync::mpsc::{self};
 use tokio::time::interval;
 
-use crate::command::{Command, CommandControl, CommandEvent, CommandSerializableState};
+use crate::alert::{
+    desktop_sink, diff_summary, run_hook, AlertAction, HookEvent, HookRun, Notification,
+    NotificationSink,
+};
+use crate::command::{
+    Command, CommandControl, CommandEvent, CommandOutput, CommandSerializableState, ExecPool,
+    OutputView,
+};
 use crate::config::AppConfig;
 use crate::controls;
+use crate::history::{store_path, HistoryWriter, StoreKey};
 use crate::logging::{error, info, warn};
 use crate::mode::AppMode;
 use crate::pane::{PaneKey, PaneManager};
 use crate::ui::draw::draw_ui;
 use crate::ui::DisplayType;
 
+/// How long an alert flashes its pane's border.
+const FLASH_DURATION: Duration = Duration::from_secs(3);
+
+/// How long an alert's banner stays in the status line.
+pub const BANNER_DURATION: Duration = Duration::from_secs(10);
+
 #[derive(Debug)]
 pub enum AppControl {
     SetCommand(PaneKey, String),
@@ -26,6 +42,37 @@ pub enum AppControl {
     SetDisplay(PaneKey, DisplayType),
 }
 
+/// The change that made the app exit when `chg_exit` is set.
+#[derive(Debug)]
+pub struct OutputChange {
+    pub pane_id: usize,
+    pub exec: String,
+    pub previous: String,
+    pub current: String,
+}
+
+impl OutputChange {
+    /// The exit status telling which pane changed: its ID plus one, so it's
+    /// never the 1 of an error, up to 255 for pane 254 and any after it.
+    pub fn exit_code(&self) -> i32 {
+        self.pane_id.saturating_add(1).min(255) as i32
+    }
+}
+
+impl fmt::Display for OutputChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let old_header = format!("pane {} (previous): {}", self.pane_id, self.exec);
+        let new_header = format!("pane {} (current): {}", self.pane_id, self.exec);
+
+        let diff = TextDiff::from_lines(&self.previous, &self.current);
+        write!(
+            f,
+            "{}",
+            diff.unified_diff().header(&old_header, &new_header)
+        )
+    }
+}
+
 pub struct App {
     pub pane_manager: PaneManager,
     pub tasks: HashMap<PaneKey, Command>,
@@ -36,7 +83,15 @@ pub struct App {
     pub app_control_tx: mpsc::Sender<AppControl>,
     pub app_control_rx: mpsc::Receiver<AppControl>,
     pub config: AppConfig,
+    /// Shared by every pane to cap how many commands run at once.
+    pub pool: ExecPool,
     pub pane_area: Rect,
+    pub change_exit: Option<OutputChange>,
+    /// The last alert banner and when it was raised.
+    pub banner: Option<(String, Instant)>,
+    pub notifier: Box<dyn NotificationSink>,
+    /// Keeps each pane's runs on disk when `store_history` is set.
+    pub history_writer: HistoryWriter,
 }
 
 impl App {
@@ -64,8 +119,13 @@ impl App {
             output_tx,
             app_control_tx,
             app_control_rx,
+            pool: ExecPool::new(config.max_concurrency),
             config,
             pane_area: Rect::new(0, 0, 0, 0),
+            change_exit: None,
+            banner: None,
+            notifier: desktop_sink(),
+            history_writer: HistoryWriter::default(),
         }
     }
 
@@ -89,7 +149,12 @@ impl App {
                     match event {
                         CommandEvent::Started => {
                             if let Some(command) = self.tasks.get_mut(&id) {
-                                command.state = crate::command::CommandState::Executing;
+                                command.start_run();
+                            }
+                        }
+                        CommandEvent::Partial(stream, chunk) => {
+                            if let Some(command) = self.tasks.get_mut(&id) {
+                                command.append_partial(stream, &chunk);
                             }
                         }
                         CommandEvent::Output(out) => {
@@ -103,11 +168,21 @@ impl App {
                                 }
                             }
 
+                            self.check_output_change(id, &out);
+                            self.raise_alerts(id, &out);
+                            self.run_hooks(id, &out);
+                            self.store_output(id, &out);
+
                             if let Some(command) = self.tasks.get_mut(&id) {
                                 command.state = crate::command::CommandState::Idle;
                                 command.record_output(out, self.config.max_history);
                             }
                         }
+                        CommandEvent::Backoff(interval) => {
+                            if let Some(command) = self.tasks.get_mut(&id) {
+                                command.backed_off_interval = interval;
+                            }
+                        }
                     }
                 },
                 Some(Ok(event)) = events.next().fuse() => {
@@ -154,6 +229,140 @@ impl App {
         self.exit = true;
     }
 
+    fn check_output_change(&mut self, id: PaneKey, out: &CommandOutput) {
+        if !self.config.chg_exit || self.change_exit.is_some() {
+            return;
+        }
+
+        let Some(pane_id) = self.pane_manager.pane_key_to_friendly_id(&id) else {
+            return;
+        };
+        if self
+            .config
+            .chg_exit_pane
+            .is_some_and(|watched| watched != pane_id)
+        {
+            return;
+        }
+
+        let Some(command) = self.tasks.get(&id) else {
+            return;
+        };
+        let Some(previous) = command.last_output() else {
+            return;
+        };
+        if previous.same_output(out) {
+            return;
+        }
+
+        info!("Exiting because output of pane {} changed.", pane_id);
+        self.change_exit = Some(OutputChange {
+            pane_id,
+            exec: command.exec.clone(),
+            previous: previous.text(OutputView::Both).into_owned(),
+            current: out.text(OutputView::Both).into_owned(),
+        });
+        self.exit();
+    }
+
+    /// Carries out the actions of the pane's alerts that fire on `out`.
+    fn raise_alerts(&mut self, id: PaneKey, out: &CommandOutput) {
+        let Some(command) = self.tasks.get_mut(&id) else {
+            return;
+        };
+        let pane_id = self
+            .pane_manager
+            .pane_key_to_friendly_id(&id)
+            .unwrap_or_default();
+
+        for rule in command.fired_alerts(out) {
+            info!("Alert on pane {}: {}", pane_id, rule);
+            for action in &rule.actions {
+                match action {
+                    AlertAction::Beep => App::beep(),
+                    AlertAction::Flash => {
+                        command.flash_until = Some(Instant::now() + FLASH_DURATION)
+                    }
+                    AlertAction::Banner => {
+                        let message = format!("pane {}: {}", pane_id, rule.condition);
+                        self.banner = Some((message, Instant::now()));
+                    }
+                    AlertAction::Notify => {
+                        let current = out.text(OutputView::Both);
+                        let changes = match command.last_output() {
+                            Some(previous) => {
+                                diff_summary(&previous.text(OutputView::Both), &current)
+                            }
+                            None => "first run".to_string(),
+                        };
+                        self.notifier.send(Notification {
+                            summary: format!("Pane {}: {}", pane_id, rule.condition),
+                            body: format!("{}\n{}", command.exec, changes),
+                        });
+                    }
+                    AlertAction::Run(hook) => {
+                        let run = HookRun {
+                            pane_id,
+                            exec: &command.exec,
+                            shell: command.shell.as_deref(),
+                            event: HookEvent::Alert(rule.to_string()),
+                            previous: command.last_output(),
+                            out,
+                        };
+                        run_hook(hook, run);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runs the hooks for what `out` changed since the pane's last run,
+    /// the pane's own or else the global ones.
+    fn run_hooks(&self, id: PaneKey, out: &CommandOutput) {
+        let Some(command) = self.tasks.get(&id) else {
+            return;
+        };
+        let pane_id = self
+            .pane_manager
+            .pane_key_to_friendly_id(&id)
+            .unwrap_or_default();
+
+        let previous = command.last_output();
+        for event in HookEvent::of_run(previous, out) {
+            let Some(hook) = command
+                .hooks
+                .get(&event)
+                .or_else(|| self.config.hooks.get(&event))
+            else {
+                continue;
+            };
+            info!("Running {} hook of pane {}: {}", event, pane_id, hook);
+            let run = HookRun {
+                pane_id,
+                exec: &command.exec,
+                shell: command.shell.as_deref(),
+                event,
+                previous,
+                out,
+            };
+            run_hook(hook, run);
+        }
+    }
+
+    /// Appends `out` to the pane's history on disk.
+    fn store_output(&mut self, id: PaneKey, out: &CommandOutput) {
+        if !self.config.store_history {
+            return;
+        }
+        let Some(command) = self.tasks.get(&id) else {
+            return;
+        };
+
+        let path = store_path(&self.config.history_dir, &StoreKey::from(command));
+        self.history_writer
+            .append(path, self.config.history_retention, out.clone());
+    }
+
     fn beep() {
         print!("\x07");
         use std::io::{self, Write};
@@ -163,13 +372,7 @@ impl App {
     pub async fn set_command(&mut self, id: PaneKey, exec: String) {
         if let Some(old) = self.tasks.insert(
             id,
-            Command::spawn(
-                id,
-                exec,
-                self.config.default_display,
-                self.config.interval,
-                self.output_tx.clone(),
-            ),
+            Command::spawn(id, exec, &self.config, &self.pool, self.output_tx.clone()),
         ) {
             if let Some(h) = old.task_handle {
                 h.abort();
@@ -182,7 +385,7 @@ impl App {
         pane_manager: PaneManager,
         tasks_state: HashMap<PaneKey, CommandSerializableState>,
     ) -> io::Result<()> {
-        let running_tasks = Command::restore_tasks(tasks_state, self.output_tx.clone());
+        let running_tasks = Command::restore_tasks(tasks_state, &self.pool, self.output_tx.clone());
 
         self.pane_manager = pane_manager;
         self.tasks = running_tasks;
@@ -194,12 +397,13 @@ impl App {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::command::{CommandControl, CommandState};
+    use crate::command::{CommandControl, CommandState, ResourceUsage};
     use crate::config::AppConfig;
     use crate::pane::CardinalDirection;
     use crate::ui;
     use ratatui::backend::TestBackend;
     use ratatui::layout::Direction;
+    use std::sync::{Arc, Mutex};
     use tokio::time::error::Elapsed;
     use tokio::time::timeout;
 
@@ -292,6 +496,148 @@ mod tests {
         cleanup(app, root_pane);
     }
 
+    fn mock_output(output: &str) -> CommandOutput {
+        CommandOutput {
+            stdout: output.to_string(),
+            stderr: String::new(),
+            order: Vec::new(),
+            time: chrono::Local::now().naive_local(),
+            exit_status: Some(0),
+            duration: Duration::from_millis(1),
+            timed_out: false,
+            attempts: 1,
+            usage: None,
+            repeats: 1,
+            first_seen: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chg_exit_on_changed_output() {
+        let (mut app, root_pane) = mock_app();
+        app.config.chg_exit = true;
+
+        app.set_command(root_pane, "echo test".to_string()).await;
+        app.tasks
+            .get_mut(&root_pane)
+            .unwrap()
+            .record_output(mock_output("a\n"), app.config.max_history);
+
+        app.check_output_change(root_pane, &mock_output("a\n"));
+        assert!(!app.exit);
+
+        app.check_output_change(root_pane, &mock_output("b\n"));
+        assert!(app.exit);
+
+        let change = app.change_exit.as_ref().unwrap();
+        assert_eq!(change.pane_id, 1);
+        assert_eq!(change.exit_code(), 2);
+        assert!(change.to_string().contains("-a\n+b\n"));
+
+        cleanup(app, root_pane);
+    }
+
+    #[test]
+    fn test_change_exit_code_names_the_pane() {
+        let exit_code = |pane_id| {
+            OutputChange {
+                pane_id,
+                exec: String::new(),
+                previous: String::new(),
+                current: String::new(),
+            }
+            .exit_code()
+        };
+        assert_eq!(exit_code(1), 2);
+        assert_eq!(exit_code(2), 3);
+        assert_eq!(exit_code(254), 255);
+        assert_eq!(exit_code(1000), 255);
+    }
+
+    #[tokio::test]
+    async fn test_chg_exit_ignores_other_panes() {
+        let (mut app, root_pane) = mock_app();
+        app.config.chg_exit = true;
+        app.config.chg_exit_pane = Some(2);
+
+        app.set_command(root_pane, "echo test".to_string()).await;
+        app.tasks
+            .get_mut(&root_pane)
+            .unwrap()
+            .record_output(mock_output("a\n"), app.config.max_history);
+
+        app.check_output_change(root_pane, &mock_output("b\n"));
+        assert!(!app.exit);
+        assert!(app.change_exit.is_none());
+
+        cleanup(app, root_pane);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_history_merges_unchanged_runs() {
+        let (mut app, root_pane) = mock_app();
+        app.config.max_history = 2;
+
+        app.set_command(root_pane, "echo test".to_string()).await;
+        let command = app.tasks.get_mut(&root_pane).unwrap();
+        command.dedup_history = true;
+        let at = |minute| {
+            let mut out = mock_output("a\n");
+            out.time = chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(10, minute, 0)
+                .unwrap();
+            out
+        };
+        command.record_output(mock_output("b\n"), 2);
+        for minute in 1..=42 {
+            command.record_output(at(minute), 2);
+        }
+
+        assert_eq!(command.output_history.len(), 2);
+        assert_eq!(command.output_history[0].stdout, "b\n");
+        let unchanged = &command.output_history[1];
+        assert_eq!(unchanged.repeats, 42);
+        assert_eq!(unchanged.first_seen, Some(at(1).time));
+        assert_eq!(unchanged.time, at(42).time);
+
+        cleanup(app, root_pane);
+    }
+
+    struct RecordingSink(Arc<Mutex<Vec<Notification>>>);
+
+    impl NotificationSink for RecordingSink {
+        fn send(&self, notification: Notification) {
+            self.0.lock().unwrap().push(notification);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_alert_sends_notification() {
+        let (mut app, root_pane) = mock_app();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        app.notifier = Box::new(RecordingSink(sent.clone()));
+
+        app.set_command(root_pane, "echo test".to_string()).await;
+        let command = app.tasks.get_mut(&root_pane).unwrap();
+        command.add_alert("changed => notify".parse().unwrap());
+        command.record_output(mock_output("a\n"), app.config.max_history);
+
+        app.raise_alerts(root_pane, &mock_output("a\n"));
+        assert!(sent.lock().unwrap().is_empty());
+
+        app.raise_alerts(root_pane, &mock_output("b\n"));
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec![Notification {
+                summary: "Pane 1: changed".to_string(),
+                body: "echo test\n+1 -1 lines: b".to_string(),
+            }]
+        );
+
+        cleanup(app, root_pane);
+    }
+
     #[tokio::test]
     async fn test_app_control_pause() {
         let (mut app, root_pane) = mock_app();
@@ -351,6 +697,11 @@ mod tests {
                                 command.state = crate::command::CommandState::Executing;
                             }
                         }
+                        CommandEvent::Partial(stream, chunk) => {
+                            if let Some(command) = app.tasks.get_mut(&id) {
+                                command.append_partial(stream, &chunk);
+                            }
+                        }
                         CommandEvent::Output(out) => {
                             if let Some(command) = app.tasks.get_mut(&id) {
                                 command.state = crate::command::CommandState::Idle;
@@ -358,6 +709,7 @@ mod tests {
                                 output_count += 1;
                             }
                         }
+                        CommandEvent::Backoff(_) => {}
                     }
                 } else {
                     break;
@@ -479,6 +831,19 @@ mod tests {
         let result = simulate_app(&mut app, 1);
         assert!(result.await.is_ok(), "Timed out waiting for CommandEvents");
 
+        // Pin what the run cost, it differs between machines.
+        let command = app.tasks.get_mut(&root_pane).unwrap();
+        for out in command.output_history.iter_mut() {
+            out.duration = Duration::from_millis(5);
+            out.usage = Some(ResourceUsage {
+                user_time: Duration::from_millis(3),
+                system_time: Duration::from_millis(1),
+                max_rss: 4 << 20,
+                stdout_bytes: 87,
+                stderr_bytes: 0,
+            });
+        }
+
         render_terminal(&mut terminal, &mut app);
         assert_ui_snapshot("observe_mode", terminal.backend().to_string());
 
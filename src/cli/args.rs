@@ -1,6 +1,11 @@
+use std::time::Duration;
+
 use clap::Parser;
 use clap_verbosity_flag::{ErrorLevel, Verbosity};
 
+use crate::alert::AlertRule;
+use crate::command::Timetable;
+use crate::config::utils::{parse_duration, parse_interval};
 use crate::ui::DisplayType;
 
 #[derive(Parser, Debug)]
@@ -10,6 +15,24 @@ pub struct Cli {
     #[arg(short, long)]
     pub beep: bool,
 
+    /// Alert when a run meets a condition, like "value > 90 => beep, flash"
+    /// or "!match /ok/ => banner, run notify-send down". Can be repeated
+    #[arg(long = "alert", value_name = "RULE")]
+    pub alerts: Vec<AlertRule>,
+
+    /// Run this shell command when the output changes. It gets the pane and
+    /// both outputs in PANE_* variables and the new output on stdin
+    #[arg(long, value_name = "HOOK")]
+    pub on_change: Option<String>,
+
+    /// Run this shell command when the command starts failing
+    #[arg(long, value_name = "HOOK")]
+    pub on_fail: Option<String>,
+
+    /// Run this shell command when the command succeeds again after failing
+    #[arg(long, value_name = "HOOK")]
+    pub on_recover: Option<String>,
+
     /// Highlight differences
     #[arg(
         short = 'd',
@@ -21,9 +44,34 @@ pub struct Cli {
     )]
     pub display: Option<DisplayType>,
 
-    /// The interval to wait between executions
-    #[arg(short = 'n', long, value_name = "SECONDS")]
-    pub interval: Option<u64>,
+    /// The interval to wait between executions, in seconds or like "250ms"
+    #[arg(short = 'n', long, value_name = "DURATION", value_parser = parse_interval)]
+    pub interval: Option<Duration>,
+
+    /// Run at these times instead of every interval: a cron expression
+    /// like "*/15 * * * *" or a list of times like "09:00,17:30"
+    #[arg(long, value_name = "SCHEDULE")]
+    pub at: Option<Timetable>,
+
+    /// Slow down while the command fails or its output doesn't change
+    #[arg(long)]
+    pub backoff: bool,
+
+    /// Run on wall clock multiples of the interval (fixed-rate scheduling)
+    #[arg(short = 'p', long = "precise")]
+    pub precise: bool,
+
+    /// Kill a command that runs longer than this, in seconds or like "1m30s"
+    #[arg(short = 't', long, value_name = "DURATION", value_parser = parse_duration)]
+    pub timeout: Option<Duration>,
+
+    /// Retry a failed command this many times before recording the failure
+    #[arg(long, value_name = "N")]
+    pub retries: Option<u32>,
+
+    /// How long to wait before each retry, in seconds or like "500ms"
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub retry_delay: Option<Duration>,
 
     #[command(flatten)]
     pub verbose: Verbosity<ErrorLevel>,
@@ -35,14 +83,32 @@ pub struct Cli {
     #[arg(short = 'e', long = "err-exit")]
     pub err_exit: bool,
 
-    /// Exit if output changes
+    /// Exit if output changes, printing the pane's ID and diff. The status is
+    /// the pane's ID plus one, at most 255
     #[arg(short = 'g', long = "chg-exit")]
     pub chg_exit: bool,
 
+    /// Only exit on change of this pane (implies --chg-exit)
+    #[arg(long = "chg-exit-pane", value_name = "PANE_ID")]
+    pub chg_exit_pane: Option<usize>,
+
+    /// Pass the command to exec instead of running it with a shell
+    #[arg(short = 'x', long = "exec")]
+    pub exec: bool,
+
     /// Max history to keep
     #[arg(short = 'm', long = "max-history", value_name = "COUNT")]
     pub max_history: Option<usize>,
 
+    /// Merge runs whose output didn't change into one history entry
+    #[arg(long = "dedup-history")]
+    pub dedup_history: bool,
+
+    /// Keep every run on disk to browse in observe mode, not just the last
+    /// max-history
+    #[arg(long = "store-history")]
+    pub store_history: bool,
+
     /// Disable line wrapping
     #[arg(short = 'w', long = "no-wrap")]
     pub no_wrap: bool,
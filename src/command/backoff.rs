@@ -0,0 +1,100 @@
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::utils::{deserialize_duration, serialize_duration};
+
+/// Slows a pane down while its command keeps failing or printing the same
+/// output, and returns it to its own interval once the output changes.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Backoff {
+    /// Shortest interval to back off to.
+    #[serde(
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub min: Duration,
+    /// Longest interval to back off to.
+    #[serde(
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub max: Duration,
+    /// How much longer each backed off wait is than the last.
+    pub multiplier: f64,
+    /// Failed or unchanged runs in a row before backing off.
+    pub after: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            min: Duration::ZERO,
+            max: Duration::from_secs(300),
+            multiplier: 2.0,
+            after: 3,
+        }
+    }
+}
+
+impl Backoff {
+    /// The interval to wait after `quiet_runs` failed or unchanged runs in a row.
+    pub fn interval(&self, base: Duration, quiet_runs: u32) -> Duration {
+        let after = self.after.max(1);
+        if quiet_runs < after {
+            return base;
+        }
+
+        let steps = (quiet_runs - after + 1).min(64) as i32;
+        let backed_off =
+            Duration::try_from_secs_f64(base.as_secs_f64() * self.multiplier.powi(steps))
+                .unwrap_or(self.max);
+
+        // Backing off never makes a pane run more often than its own interval.
+        backed_off.min(self.max).max(self.min).max(base)
+    }
+}
+
+impl fmt::Display for Backoff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "x{} after {} quiet runs ({:?} to {:?})",
+            self.multiplier, self.after, self.min, self.max
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backs_off_after_quiet_runs() {
+        let backoff = Backoff {
+            min: Duration::from_secs(10),
+            max: Duration::from_secs(60),
+            multiplier: 2.0,
+            after: 2,
+        };
+        let base = Duration::from_secs(1);
+        let intervals: Vec<u64> = (0..8)
+            .map(|runs| backoff.interval(base, runs).as_secs())
+            .collect();
+
+        assert_eq!(intervals, vec![1, 1, 10, 10, 10, 16, 32, 60]);
+    }
+
+    #[test]
+    fn test_never_shorter_than_base() {
+        let backoff = Backoff {
+            max: Duration::from_secs(5),
+            ..Backoff::default()
+        };
+        let base = Duration::from_secs(30);
+
+        assert_eq!(backoff.interval(base, 10), base);
+    }
+}
@@ -1,14 +1,24 @@
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use chrono::NaiveDateTime;
+use ratatui::text::Text;
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio::task::JoinHandle;
 
-use crate::command::serialization::naivedatetime_format;
-use crate::ui::DisplayType;
+use crate::alert::{AlertRule, Hooks};
+use crate::command::serialization::{naivedatetime_format, optional_naivedatetime_format};
+use crate::command::{Backoff, ExecPool, Extractor, ResourceUsage, TerminalSize, Timetable};
+use crate::config::utils::{
+    deserialize_duration, deserialize_interval, deserialize_optional_duration, serialize_duration,
+    serialize_optional_duration,
+};
+use crate::ui::{ChartView, DisplayType, Pipeline, Series};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CommandControl {
@@ -18,6 +28,9 @@ pub enum CommandControl {
     IntervalIncrease,
     IntervalDecrease,
     IntervalSet(Duration),
+    SetSchedulePolicy(SchedulePolicy),
+    SetBackoff(Option<Backoff>),
+    SetPty(bool),
     Execute,
 }
 
@@ -40,39 +53,415 @@ impl fmt::Display for CommandState {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Which of the captured streams a pane displays.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OutputView {
+    #[default]
+    Both,
+    Stdout,
+    Stderr,
+}
+
+impl OutputView {
+    pub fn next(self) -> Self {
+        match self {
+            OutputView::Both => OutputView::Stdout,
+            OutputView::Stdout => OutputView::Stderr,
+            OutputView::Stderr => OutputView::Both,
+        }
+    }
+}
+
+impl fmt::Display for OutputView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputView::Both => write!(f, "stdout+stderr"),
+            OutputView::Stdout => write!(f, "stdout"),
+            OutputView::Stderr => write!(f, "stderr"),
+        }
+    }
+}
+
+/// How runs are spaced out when a command takes a while to complete.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchedulePolicy {
+    /// Wait the full interval after each run finishes.
+    #[default]
+    FixedDelay,
+    /// Run on wall clock multiples of the interval. A run that overruns its
+    /// slot delays the next one instead of overlapping it.
+    FixedRate,
+    /// Run at a fixed rate, dropping ticks while the previous run is going.
+    SkipIfRunning,
+}
+
+impl SchedulePolicy {
+    pub fn next(self) -> Self {
+        match self {
+            SchedulePolicy::FixedDelay => SchedulePolicy::FixedRate,
+            SchedulePolicy::FixedRate => SchedulePolicy::SkipIfRunning,
+            SchedulePolicy::SkipIfRunning => SchedulePolicy::FixedDelay,
+        }
+    }
+}
+
+impl fmt::Display for SchedulePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchedulePolicy::FixedDelay => write!(f, "fixed-delay"),
+            SchedulePolicy::FixedRate => write!(f, "fixed-rate"),
+            SchedulePolicy::SkipIfRunning => write!(f, "skip-if-running"),
+        }
+    }
+}
+
+/// When a pane's command runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub interval: Duration,
+    pub policy: SchedulePolicy,
+    /// Run at these wall clock times instead of every `interval`.
+    pub timetable: Option<Timetable>,
+    pub backoff: Option<Backoff>,
+}
+
+/// How a pane's command line is turned into a process.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecMode {
+    /// Run through `$SHELL -c`.
+    #[default]
+    Shell,
+    /// Split into argv with shell-words rules and exec'd without a shell.
+    Direct,
+}
+
+impl fmt::Display for ExecMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExecMode::Shell => write!(f, "shell"),
+            ExecMode::Direct => write!(f, "direct"),
+        }
+    }
+}
+
+/// How often a failed run is tried again before its failure is recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    /// How long to wait before each retry.
+    #[serde(
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl fmt::Display for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.retries {
+            0 => write!(f, "none"),
+            n => write!(f, "{} after {:?}", n, self.delay),
+        }
+    }
+}
+
+/// Settings that apply to every run of a pane's command.
+#[derive(Debug, Clone)]
+pub struct ExecOptions {
+    pub timeout: Option<Duration>,
+    pub retry: RetryPolicy,
+    pub exec_mode: ExecMode,
+    pub shell: Option<String>,
+    pub cwd: Option<PathBuf>,
+    pub env: BTreeMap<String, String>,
+    pub pty: bool,
+    /// Size the pty is opened with and resized to while the command runs.
+    pub terminal_size: watch::Receiver<TerminalSize>,
+    pub pool: ExecPool,
+}
+
+impl Default for ExecOptions {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            retry: RetryPolicy::default(),
+            exec_mode: ExecMode::default(),
+            shell: None,
+            cwd: None,
+            env: BTreeMap::new(),
+            pty: false,
+            terminal_size: watch::channel(TerminalSize::default()).1,
+            pool: ExecPool::default(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CommandSerializableState {
     pub exec: String,
+    #[serde(
+        deserialize_with = "deserialize_interval",
+        serialize_with = "serialize_duration"
+    )]
     pub interval: Duration,
+    #[serde(default)]
+    pub schedule_policy: SchedulePolicy,
+    #[serde(default)]
+    pub timetable: Option<Timetable>,
+    #[serde(default)]
+    pub backoff: Option<Backoff>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_duration",
+        serialize_with = "serialize_optional_duration"
+    )]
+    pub timeout: Option<Duration>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    #[serde(default)]
+    pub exec_mode: ExecMode,
+    #[serde(default)]
+    pub shell: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
     pub output_history: VecDeque<CommandOutput>,
     pub state: CommandState,
     pub display_type: DisplayType,
+    #[serde(default)]
+    pub output_view: OutputView,
+    #[serde(default)]
+    pub series: Series,
+    #[serde(default)]
+    pub extractor: Option<Extractor>,
+    #[serde(default)]
+    pub transforms: Pipeline,
+    #[serde(default)]
+    pub secondary_axis: bool,
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    #[serde(default)]
+    pub hooks: Hooks,
+    #[serde(default)]
+    pub strip_ansi: bool,
+    #[serde(default)]
+    pub dedup_history: bool,
+    #[serde(default)]
+    pub pty: bool,
 }
 
 #[derive(Debug)]
 pub struct Command {
     pub exec: String,
     pub interval: Duration,
+    pub schedule_policy: SchedulePolicy,
+    pub timetable: Option<Timetable>,
+    pub backoff: Option<Backoff>,
+    /// The longer interval the pane is waiting while backed off.
+    pub backed_off_interval: Option<Duration>,
+    pub timeout: Option<Duration>,
+    pub retry: RetryPolicy,
+    pub exec_mode: ExecMode,
+    pub shell: Option<String>,
+    pub cwd: Option<PathBuf>,
+    pub env: BTreeMap<String, String>,
     pub output_history: VecDeque<CommandOutput>,
     pub state: CommandState,
     pub display_type: DisplayType,
+    pub output_view: OutputView,
+    /// What charts and sparklines plot.
+    pub series: Series,
+    /// Finds the value to plot in output that isn't a bare number.
+    pub extractor: Option<Extractor>,
+    /// Applied to each series before it's plotted, e.g. a rate for counters.
+    pub transforms: Pipeline,
+    /// Chart every series after the first against a second y-axis.
+    pub secondary_axis: bool,
+    pub chart_view: ChartView,
+    /// Checked against every run, in the order they were added.
+    pub alerts: Vec<AlertRule>,
+    /// Whether each alert's condition held on the last run, `None` before
+    /// the first one.
+    pub alerts_held: Vec<Option<bool>>,
+    /// The border flashes until then after an alert.
+    pub flash_until: Option<Instant>,
+    /// Run on output events instead of the global hooks.
+    pub hooks: Hooks,
+    /// Show the output without its ANSI colors.
+    pub strip_ansi: bool,
+    /// Merge runs with unchanged output into the last history entry.
+    pub dedup_history: bool,
+    /// Run the command in a pseudo-terminal instead of with piped output.
+    pub pty: bool,
+    /// Size of the pane's content area, which the pty follows.
+    pub terminal_size: watch::Sender<TerminalSize>,
+    pub partial_output: PartialOutput,
+    /// Bumped whenever the live or last output changes.
+    pub output_generation: u64,
+    /// The output as last styled for display, with the generation, view and
+    /// ANSI handling it was styled for, so it's styled once per output.
+    pub formatted: RefCell<Option<(FormatKey, Text<'static>)>>,
     pub task_handle: Option<JoinHandle<()>>,
     pub control_tx: mpsc::Sender<CommandControl>,
 }
 
+/// What the displayed output was styled from.
+pub type FormatKey = (u64, bool, OutputView, bool);
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CommandOutput {
-    pub output: String,
+    // Sessions saved before the streams were split only have `output`.
+    #[serde(alias = "output")]
+    pub stdout: String,
+    #[serde(default)]
+    pub stderr: String,
+    /// The stream each output line came from, in arrival order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<OutputStream>,
     #[serde(with = "naivedatetime_format")]
     pub time: NaiveDateTime,
     pub exit_status: Option<i32>,
     pub duration: Duration,
+    #[serde(default)]
+    pub timed_out: bool,
+    /// How many times the command was run, more than once when it was retried.
+    #[serde(default = "one")]
+    pub attempts: u32,
+    /// Unknown when the command couldn't be run, or in older sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ResourceUsage>,
+    /// How many runs in a row gave this output, when unchanged runs are
+    /// merged into one entry.
+    #[serde(default = "one")]
+    pub repeats: u32,
+    /// When the first of the merged runs was, `time` being the last.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "optional_naivedatetime_format"
+    )]
+    pub first_seen: Option<NaiveDateTime>,
+}
+
+fn one() -> u32 {
+    1
+}
+
+/// What a running command has output so far, kept by stream like a run's.
+#[derive(Debug, Default)]
+pub struct PartialOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub order: Vec<OutputStream>,
+}
+
+impl PartialOutput {
+    pub fn push(&mut self, stream: OutputStream, chunk: &str) {
+        match stream {
+            OutputStream::Stdout => self.stdout.push_str(chunk),
+            OutputStream::Stderr => self.stderr.push_str(chunk),
+        }
+        self.order.push(stream);
+    }
+
+    pub fn clear(&mut self) {
+        self.stdout.clear();
+        self.stderr.clear();
+        self.order.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn lines(&self, view: OutputView) -> Vec<(OutputStream, &str)> {
+        stream_lines(&self.stdout, &self.stderr, &self.order, view)
+    }
 }
 
 #[derive(Debug)]
 pub enum CommandEvent {
     Started,
+    /// A line of output, or what there is of it at the end.
+    Partial(OutputStream, String),
     Output(CommandOutput),
+    /// The interval the pane backed off to, `None` once it's back to its own.
+    Backoff(Option<Duration>),
+}
+
+impl CommandOutput {
+    /// Output lines of the selected streams, interleaved in arrival order.
+    pub fn lines(&self, view: OutputView) -> Vec<(OutputStream, &str)> {
+        stream_lines(&self.stdout, &self.stderr, &self.order, view)
+    }
+
+    pub fn text(&self, view: OutputView) -> Cow<'_, str> {
+        match view {
+            OutputView::Stdout => Cow::Borrowed(&self.stdout),
+            OutputView::Stderr => Cow::Borrowed(&self.stderr),
+            OutputView::Both if self.stderr.is_empty() => Cow::Borrowed(&self.stdout),
+            OutputView::Both if self.stdout.is_empty() => Cow::Borrowed(&self.stderr),
+            OutputView::Both => {
+                let mut text = String::with_capacity(self.stdout.len() + self.stderr.len());
+                for (_, line) in self.lines(view) {
+                    text.push_str(line);
+                    text.push('\n');
+                }
+                Cow::Owned(text)
+            }
+        }
+    }
+
+    pub fn same_output(&self, other: &CommandOutput) -> bool {
+        self.stdout == other.stdout && self.stderr == other.stderr
+    }
+}
+
+/// Lines of the selected streams, interleaved in arrival order.
+fn stream_lines<'a>(
+    stdout: &'a str,
+    stderr: &'a str,
+    order: &[OutputStream],
+    view: OutputView,
+) -> Vec<(OutputStream, &'a str)> {
+    let mut stdout = stdout.lines();
+    let mut stderr = stderr.lines();
+
+    match view {
+        OutputView::Stdout => stdout.map(|l| (OutputStream::Stdout, l)).collect(),
+        OutputView::Stderr => stderr.map(|l| (OutputStream::Stderr, l)).collect(),
+        OutputView::Both => {
+            let mut lines: Vec<_> = order
+                .iter()
+                .filter_map(|stream| match stream {
+                    OutputStream::Stdout => stdout.next().map(|l| (*stream, l)),
+                    OutputStream::Stderr => stderr.next().map(|l| (*stream, l)),
+                })
+                .collect();
+
+            // Anything not covered by `order`, e.g. from older sessions.
+            lines.extend(stdout.map(|l| (OutputStream::Stdout, l)));
+            lines.extend(stderr.map(|l| (OutputStream::Stderr, l)));
+            lines
+        }
+    }
 }
 
 impl Command {
@@ -80,9 +469,95 @@ impl Command {
         CommandSerializableState {
             exec: self.exec.clone(),
             interval: self.interval,
+            schedule_policy: self.schedule_policy,
+            timetable: self.timetable.clone(),
+            backoff: self.backoff,
+            timeout: self.timeout,
+            retry: self.retry,
+            exec_mode: self.exec_mode,
+            shell: self.shell.clone(),
+            cwd: self.cwd.clone(),
+            env: self.env.clone(),
             output_history: self.output_history.clone(),
             state: self.state,
             display_type: self.display_type,
+            output_view: self.output_view,
+            series: self.series,
+            extractor: self.extractor.clone(),
+            transforms: self.transforms.clone(),
+            secondary_axis: self.secondary_axis,
+            alerts: self.alerts.clone(),
+            hooks: self.hooks.clone(),
+            strip_ansi: self.strip_ansi,
+            dedup_history: self.dedup_history,
+            pty: self.pty,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_output(stdout: &str, stderr: &str, order: Vec<OutputStream>) -> CommandOutput {
+        CommandOutput {
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+            order,
+            time: chrono::Local::now().naive_local(),
+            exit_status: Some(1),
+            duration: Duration::from_millis(1),
+            timed_out: false,
+            attempts: 1,
+            usage: None,
+            repeats: 1,
+            first_seen: None,
         }
     }
+
+    #[test]
+    fn test_partial_output_keeps_streams_apart() {
+        use OutputStream::{Stderr, Stdout};
+
+        let mut partial = PartialOutput::default();
+        partial.push(Stdout, "a\n");
+        partial.push(Stderr, "b\n");
+        partial.push(Stdout, "c");
+
+        assert_eq!(
+            partial.lines(OutputView::Both),
+            vec![(Stdout, "a"), (Stderr, "b"), (Stdout, "c")]
+        );
+        assert_eq!(partial.lines(OutputView::Stderr), vec![(Stderr, "b")]);
+        partial.clear();
+        assert!(partial.is_empty());
+    }
+
+    #[test]
+    fn test_text_interleaves_streams_in_arrival_order() {
+        use OutputStream::{Stderr, Stdout};
+
+        let out = mock_output("a\nc\n", "b\n", vec![Stdout, Stderr, Stdout]);
+
+        assert_eq!(out.text(OutputView::Both), "a\nb\nc\n");
+        assert_eq!(out.text(OutputView::Stdout), "a\nc\n");
+        assert_eq!(out.text(OutputView::Stderr), "b\n");
+        assert_eq!(out.lines(OutputView::Both)[1], (Stderr, "b"));
+    }
+
+    #[test]
+    fn test_old_output_field_is_read_as_stdout() {
+        let old = r#"
+            output = "hello\n"
+            time = "Mon  1 Jan 2024 10:00:00"
+            exit_status = 0
+            duration = { secs = 0, nanos = 1000 }
+        "#;
+
+        let out: CommandOutput = toml::from_str(old).unwrap();
+
+        assert_eq!(out.stdout, "hello\n");
+        assert!(out.stderr.is_empty());
+        assert_eq!(out.text(OutputView::Both), "hello\n");
+    }
 }
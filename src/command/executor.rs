@@ -1,72 +1,749 @@
-use std::io;
-use std::process::Stdio;
-use std::time::Instant;
+use std::os::fd::OwnedFd;
+use std::process::{ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use std::{env, io};
 
 use chrono::{Local, NaiveDateTime};
-use tokio::io::{AsyncReadExt, BufReader};
-use tokio::process::Command as SysCommand;
-use tokio::sync::mpsc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command as SysCommand};
+use tokio::sync::{mpsc, watch};
+use tokio::task::{self, JoinHandle};
+use tokio::time;
 
-use crate::command::{CommandEvent, CommandOutput};
+use crate::command::pty::{self, PtyReader, TerminalSize};
+use crate::command::{
+    CommandEvent, CommandOutput, ExecMode, ExecOptions, OutputStream, ResourceUsage,
+};
 use crate::logging::warn;
 use crate::pane::PaneKey;
 
+/// Waits for a child to exit without reaping it, for the resources it used.
+/// The wait is polled until it resolves once, then what it read is kept, so
+/// a run cut short by its timeout can wait again.
+struct Exit {
+    wait: Option<JoinHandle<Result<libc::rusage, io::Error>>>,
+    usage: Option<libc::rusage>,
+}
+
+impl Exit {
+    fn is_finished(&self) -> bool {
+        self.wait.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    async fn usage(&mut self) -> Result<Option<libc::rusage>, io::Error> {
+        if let Some(wait) = &mut self.wait {
+            let joined = wait.await;
+            self.wait = None;
+            self.usage = joined.map_err(io::Error::other)?.ok();
+        }
+        Ok(self.usage)
+    }
+}
+
+/// How long a timed out command gets to exit after SIGTERM before SIGKILL.
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// The process group a child leads, killed when the run is dropped before
+/// the child was reaped, e.g. when its pane is closed or its command replaced.
+struct ProcessGroup(Option<libc::pid_t>);
+
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        if let Some(pgid) = self.0 {
+            unsafe { libc::killpg(pgid, libc::SIGKILL) };
+        }
+    }
+}
+
 impl super::Command {
     pub async fn run_and_send_output(
         id: PaneKey,
         exec: &str,
+        options: &ExecOptions,
         output_tx: mpsc::Sender<(PaneKey, CommandEvent)>,
-    ) -> Result<(), io::Error> {
+    ) -> Result<CommandOutput, io::Error> {
+        let mut attempts = 1;
+        let result = loop {
+            let start = Instant::now();
+            // Every attempt starts with `Started`, which clears the live
+            // output a failed one streamed.
+            let result = Self::run_once(id, exec, options, &output_tx).await;
+
+            let succeeded = matches!(&result, Ok(out) if out.exit_status == Some(0));
+            if succeeded || attempts > options.retry.retries {
+                break result.map_err(|e| (e, start.elapsed()));
+            }
+
+            warn!(
+                "Pane {:?} attempt {} failed, retrying in {:?}",
+                id, attempts, options.retry.delay
+            );
+            time::sleep(options.retry.delay).await;
+            attempts += 1;
+        };
+
+        let (cmd_output, result) = match result {
+            Ok(mut cmd_output) => {
+                cmd_output.attempts = attempts;
+                (cmd_output, Ok(()))
+            }
+            // Report the failure in the pane, it would otherwise be left executing.
+            Err((e, duration)) => {
+                let cmd_output = CommandOutput {
+                    stdout: String::new(),
+                    stderr: format!("Failed to run command: {}\n", e),
+                    order: Vec::new(),
+                    time: Local::now().naive_local(),
+                    exit_status: None,
+                    duration,
+                    timed_out: false,
+                    attempts,
+                    usage: None,
+                    repeats: 1,
+                    first_seen: None,
+                };
+                (cmd_output, Err(e))
+            }
+        };
+
+        if let Err(e) = output_tx
+            .send((id, CommandEvent::Output(cmd_output.clone())))
+            .await
+        {
+            warn!("Failed to send output for pane {:?}: {}", id, e);
+        }
+
+        result.map(|()| cmd_output)
+    }
+
+    /// Runs the command once, streaming its output as it arrives.
+    async fn run_once(
+        id: PaneKey,
+        exec: &str,
+        options: &ExecOptions,
+        output_tx: &mpsc::Sender<(PaneKey, CommandEvent)>,
+    ) -> Result<CommandOutput, io::Error> {
+        let _permit = options.pool.acquire().await;
+
         if let Err(e) = output_tx.send((id, CommandEvent::Started)).await {
             warn!("Failed to send output for pane {:?}: {}", id, e);
         }
 
         let start = Instant::now();
 
-        let mut command = SysCommand::new("sh")
-            .arg("-c")
-            .arg(exec)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+        let pty_size = options.pty.then(|| options.terminal_size.clone());
+
+        let (mut command, pty_master) = Self::spawn_child(exec, options, pty_size.as_ref())?;
+        let mut group = ProcessGroup(command.id().map(|pid| pid as libc::pid_t));
+        let mut exit = Self::wait_for_exit(&command)?;
 
+        let stdout: Option<Box<dyn AsyncRead + Unpin + Send>> = match &pty_master {
+            Some(master) => Some(Box::new(PtyReader::new(master.try_clone()?)?)),
+            None => command
+                .stdout
+                .take()
+                .map(|s| Box::new(s) as Box<dyn AsyncRead + Unpin + Send>),
+        };
+        let stderr = command.stderr.take();
         let mut stdout_output = String::new();
         let mut stderr_output = String::new();
+        // Raw byte counts, taken before decoding may make invalid bytes longer.
+        let (mut stdout_bytes, mut stderr_bytes) = (0, 0);
+        let order = Mutex::new(Vec::new());
 
-        if let Some(stdout) = command.stdout.take() {
-            let mut reader = BufReader::new(stdout);
-            reader.read_to_string(&mut stdout_output).await?;
-        }
-        if let Some(stderr) = command.stderr.take() {
-            let mut reader = BufReader::new(stderr);
-            reader.read_to_string(&mut stderr_output).await?;
+        let read_and_wait = async {
+            // Both pipes are drained at the same time so a child filling one of
+            // them can't block while we wait on the other.
+            tokio::try_join!(
+                Self::stream_output(
+                    id,
+                    OutputStream::Stdout,
+                    stdout,
+                    output_tx,
+                    &mut stdout_output,
+                    &mut stdout_bytes,
+                    &order
+                ),
+                Self::stream_output(
+                    id,
+                    OutputStream::Stderr,
+                    stderr,
+                    output_tx,
+                    &mut stderr_output,
+                    &mut stderr_bytes,
+                    &order
+                ),
+            )?;
+            Self::exited(&mut command, &mut exit).await
+        };
+        let run = async {
+            tokio::select! {
+                status = read_and_wait => status,
+                _ = Self::follow_resizes(id, pty_master.as_ref(), pty_size) => unreachable!(),
+            }
+        };
+
+        let finished = match options.timeout {
+            Some(limit) => time::timeout(limit, run).await.ok(),
+            None => Some(run.await),
         }
+        .transpose()?;
 
-        let status = command.wait().await?;
+        let timed_out = finished.is_none();
+        let (status, usage) = match finished {
+            Some(exited) => exited,
+            None => {
+                warn!("Pane {:?} command timed out, terminating: {}", id, exec);
+                Self::terminate(&mut command, &mut exit).await?
+            }
+        };
+        group.0 = None;
 
         let duration = start.elapsed();
 
-        let output_message = if status.success() {
-            stdout_output
-        } else {
-            format!(
-                "Command failed with status: {}. Error: {}",
-                status, stderr_output
-            )
-        };
+        let usage = usage.map(|usage| ResourceUsage::new(&usage, stdout_bytes, stderr_bytes));
 
         let now_datetime: NaiveDateTime = Local::now().naive_local();
-        let cmd_output = CommandOutput {
-            output: output_message,
+        Ok(CommandOutput {
+            stdout: stdout_output,
+            stderr: stderr_output,
+            order: order.into_inner().unwrap_or_default(),
             time: now_datetime,
             exit_status: status.code(),
             duration: duration,
+            timed_out,
+            attempts: 1,
+            usage,
+            repeats: 1,
+            first_seen: None,
+        })
+    }
+
+    fn spawn_child(
+        exec: &str,
+        options: &ExecOptions,
+        pty_size: Option<&watch::Receiver<TerminalSize>>,
+    ) -> Result<(Child, Option<OwnedFd>), io::Error> {
+        let mut sys_command = match options.exec_mode {
+            ExecMode::Shell => {
+                let mut sys_command = SysCommand::new(shell_program(options.shell.as_deref()));
+                sys_command.arg("-c").arg(exec);
+                sys_command
+            }
+            ExecMode::Direct => {
+                let argv = shell_words::split(exec)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                let Some((program, args)) = argv.split_first() else {
+                    return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty command"));
+                };
+
+                let mut sys_command = SysCommand::new(program);
+                sys_command.args(args);
+                sys_command
+            }
         };
 
-        if let Err(e) = output_tx.send((id, CommandEvent::Output(cmd_output))).await {
-            warn!("Failed to send output for pane {:?}: {}", id, e);
+        sys_command.envs(&options.env).kill_on_drop(true);
+        if let Some(cwd) = &options.cwd {
+            sys_command.current_dir(cwd);
+        }
+
+        // Our copies of the pty slave are dropped with `sys_command`, so reads
+        // end when the child exits.
+        match pty_size {
+            Some(size_rx) => {
+                let (master, slave) = pty::open(*size_rx.borrow())?;
+                sys_command
+                    .stdin(Stdio::from(slave.try_clone()?))
+                    .stdout(Stdio::from(slave.try_clone()?))
+                    .stderr(Stdio::from(slave));
+                // The child leads a new session, so it also gets its own
+                // process group for `terminate`.
+                unsafe { sys_command.pre_exec(pty::attach_controlling_terminal) };
+                Ok((sys_command.spawn()?, Some(master)))
+            }
+            None => {
+                let child = sys_command
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .process_group(0)
+                    .spawn()?;
+                Ok((child, None))
+            }
+        }
+    }
+
+    async fn stream_output<R: AsyncRead + Unpin>(
+        id: PaneKey,
+        kind: OutputStream,
+        stream: Option<R>,
+        partial_tx: &mpsc::Sender<(PaneKey, CommandEvent)>,
+        collected: &mut String,
+        read: &mut u64,
+        order: &Mutex<Vec<OutputStream>>,
+    ) -> Result<(), io::Error> {
+        let Some(stream) = stream else {
+            return Ok(());
+        };
+
+        let mut reader = BufReader::new(stream);
+        let mut buf = Vec::new();
+
+        loop {
+            buf.clear();
+            if reader.read_until(b'\n', &mut buf).await? == 0 {
+                break;
+            }
+            *read += buf.len() as u64;
+
+            let chunk = String::from_utf8_lossy(&buf).into_owned();
+            collected.push_str(&chunk);
+            if let Ok(mut order) = order.lock() {
+                order.push(kind);
+            }
+
+            if let Err(e) = partial_tx
+                .send((id, CommandEvent::Partial(kind, chunk)))
+                .await
+            {
+                warn!("Failed to send partial output for pane {:?}: {}", id, e);
+            }
         }
 
         Ok(())
     }
+
+    /// Keeps the pty size in step with the pane. Never completes.
+    async fn follow_resizes(
+        id: PaneKey,
+        master: Option<&OwnedFd>,
+        size_rx: Option<watch::Receiver<TerminalSize>>,
+    ) {
+        if let (Some(master), Some(mut size_rx)) = (master, size_rx) {
+            while size_rx.changed().await.is_ok() {
+                let size = *size_rx.borrow_and_update();
+                if let Err(e) = pty::resize(master, size) {
+                    warn!("Failed to resize pty for pane {:?}: {}", id, e);
+                }
+            }
+        }
+        std::future::pending().await
+    }
+
+    /// Stops the whole process group of a child, politely first.
+    async fn terminate(
+        child: &mut Child,
+        exit: &mut Exit,
+    ) -> Result<(ExitStatus, Option<libc::rusage>), io::Error> {
+        if let Some(pid) = child.id().filter(|_| !exit.is_finished()) {
+            // The child was spawned as the leader of its own process group.
+            let pgid = pid as libc::pid_t;
+
+            unsafe { libc::killpg(pgid, libc::SIGTERM) };
+            if let Ok(exited) = time::timeout(KILL_GRACE_PERIOD, Self::exited(child, exit)).await {
+                return exited;
+            }
+
+            unsafe { libc::killpg(pgid, libc::SIGKILL) };
+        }
+        Self::exited(child, exit).await
+    }
+
+    /// Waits on a blocking thread for the child to exit, reading the
+    /// resources it used while it's a zombie. Tokio still owns the child and
+    /// reaps it, so it can't reap a pid reused by another pane's child.
+    fn wait_for_exit(child: &Child) -> Result<Exit, io::Error> {
+        let pid = child
+            .id()
+            .ok_or_else(|| io::Error::other("child was already reaped"))?
+            as libc::pid_t;
+
+        Ok(Exit {
+            wait: Some(task::spawn_blocking(move || usage_at_exit(pid))),
+            usage: None,
+        })
+    }
+
+    /// The exit status and, where it could be read, resource usage of the
+    /// child once it's reaped.
+    async fn exited(
+        child: &mut Child,
+        exit: &mut Exit,
+    ) -> Result<(ExitStatus, Option<libc::rusage>), io::Error> {
+        let usage = exit.usage().await?;
+        let status = child.wait().await?;
+        Ok((status, usage))
+    }
+}
+
+/// The shell commands run with: the configured one, else `$SHELL`, else `sh`.
+pub fn shell_program(shell: Option<&str>) -> String {
+    shell
+        .map(str::to_string)
+        .or_else(|| env::var("SHELL").ok().filter(|s| !s.is_empty()))
+        .unwrap_or_else(|| "sh".to_string())
+}
+
+/// Blocks until `pid` exits and returns what it used, leaving it unreaped.
+/// The `waitid` syscall, unlike the libc wrapper, also reports the usage.
+#[cfg(target_os = "linux")]
+fn usage_at_exit(pid: libc::pid_t) -> Result<libc::rusage, io::Error> {
+    let mut info = unsafe { std::mem::zeroed::<libc::siginfo_t>() };
+    let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+    loop {
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_waitid,
+                libc::P_PID,
+                pid,
+                &mut info as *mut libc::siginfo_t,
+                libc::WEXITED | libc::WNOWAIT,
+                &mut usage as *mut libc::rusage,
+            )
+        };
+        if ret >= 0 {
+            return Ok(usage);
+        }
+        let e = io::Error::last_os_error();
+        if e.kind() != io::ErrorKind::Interrupted {
+            return Err(e);
+        }
+    }
+}
+
+/// Elsewhere the usage of an unreaped child can't be read, so runs have none.
+#[cfg(not(target_os = "linux"))]
+fn usage_at_exit(_pid: libc::pid_t) -> Result<libc::rusage, io::Error> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "resource usage needs waitid",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{Command, RetryPolicy};
+
+    #[tokio::test]
+    async fn test_output_is_streamed_before_completion() {
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+
+        Command::run_and_send_output(
+            PaneKey::default(),
+            "echo one; echo two >&2",
+            &ExecOptions::default(),
+            output_tx,
+        )
+        .await
+        .unwrap();
+
+        let mut partials = Vec::new();
+        let mut output = None;
+        while let Ok((_, event)) = output_rx.try_recv() {
+            match event {
+                CommandEvent::Partial(_, chunk) => partials.push(chunk),
+                CommandEvent::Output(out) => output = Some(out),
+                CommandEvent::Started | CommandEvent::Backoff(_) => {}
+            }
+        }
+
+        partials.sort();
+        assert_eq!(partials, vec!["one\n", "two\n"]);
+
+        let output = output.unwrap();
+        assert_eq!(output.stdout, "one\n");
+        assert_eq!(output.stderr, "two\n");
+    }
+
+    #[tokio::test]
+    async fn test_timeout_kills_command() {
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+
+        let start = Instant::now();
+        Command::run_and_send_output(
+            PaneKey::default(),
+            "echo started; sleep 10",
+            &ExecOptions {
+                timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            },
+            output_tx,
+        )
+        .await
+        .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let mut output = None;
+        while let Ok((_, event)) = output_rx.try_recv() {
+            if let CommandEvent::Output(out) = event {
+                output = Some(out);
+            }
+        }
+
+        let output = output.unwrap();
+        assert!(output.timed_out);
+        assert_eq!(output.stdout, "started\n");
+    }
+
+    #[tokio::test]
+    async fn test_records_resource_usage() {
+        let (output_tx, _output_rx) = mpsc::channel(100);
+
+        let output = Command::run_and_send_output(
+            PaneKey::default(),
+            "printf 12345; printf ab >&2",
+            &ExecOptions::default(),
+            output_tx,
+        )
+        .await
+        .unwrap();
+
+        let usage = output.usage.unwrap();
+        assert_eq!((usage.stdout_bytes, usage.stderr_bytes), (5, 2));
+        assert!(usage.max_rss > 0);
+    }
+
+    #[tokio::test]
+    async fn test_counts_output_bytes_before_decoding() {
+        let (output_tx, _output_rx) = mpsc::channel(100);
+
+        let output = Command::run_and_send_output(
+            PaneKey::default(),
+            r"printf '\377\376'",
+            &ExecOptions::default(),
+            output_tx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(output.usage.unwrap().stdout_bytes, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_runs_are_retried() {
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+        let marker = env::temp_dir().join(format!("pane-retry-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+
+        // Fails on the first attempt only.
+        let exec = format!(
+            "test -e {0} && echo ok || {{ touch {0}; exit 1; }}",
+            marker.display()
+        );
+        let output = Command::run_and_send_output(
+            PaneKey::default(),
+            &exec,
+            &ExecOptions {
+                retry: RetryPolicy {
+                    retries: 2,
+                    delay: Duration::from_millis(10),
+                },
+                ..Default::default()
+            },
+            output_tx,
+        )
+        .await
+        .unwrap();
+        std::fs::remove_file(&marker).unwrap();
+
+        assert_eq!(output.stdout, "ok\n");
+        assert_eq!(output.attempts, 2);
+
+        let mut outputs = 0;
+        while let Ok((_, event)) = output_rx.try_recv() {
+            if let CommandEvent::Output(_) = event {
+                outputs += 1;
+            }
+        }
+        assert_eq!(outputs, 1);
+    }
+
+    /// The live output of each attempt, split where the next one started.
+    fn attempt_partials(
+        output_rx: &mut mpsc::Receiver<(PaneKey, CommandEvent)>,
+    ) -> Vec<Vec<String>> {
+        let mut attempts = Vec::new();
+        while let Ok((_, event)) = output_rx.try_recv() {
+            match event {
+                CommandEvent::Started => attempts.push(Vec::new()),
+                CommandEvent::Partial(_, chunk) => attempts.last_mut().unwrap().push(chunk),
+                CommandEvent::Output(_) | CommandEvent::Backoff(_) => {}
+            }
+        }
+        attempts
+    }
+
+    #[tokio::test]
+    async fn test_first_attempt_is_streamed_with_retries() {
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+
+        Command::run_and_send_output(
+            PaneKey::default(),
+            "echo ok",
+            &ExecOptions {
+                retry: RetryPolicy {
+                    retries: 2,
+                    delay: Duration::from_millis(10),
+                },
+                ..Default::default()
+            },
+            output_tx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(attempt_partials(&mut output_rx), vec![vec!["ok\n"]]);
+    }
+
+    #[tokio::test]
+    async fn test_each_attempt_streams_after_its_start() {
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+        let marker = env::temp_dir().join(format!("pane-retry-stream-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+
+        let exec = format!(
+            "test -e {0} && echo ok || {{ touch {0}; echo failed; exit 1; }}",
+            marker.display()
+        );
+        Command::run_and_send_output(
+            PaneKey::default(),
+            &exec,
+            &ExecOptions {
+                retry: RetryPolicy {
+                    retries: 1,
+                    delay: Duration::from_millis(10),
+                },
+                ..Default::default()
+            },
+            output_tx,
+        )
+        .await
+        .unwrap();
+        std::fs::remove_file(&marker).unwrap();
+
+        // The pane clears the failed attempt's output when the retry starts.
+        assert_eq!(
+            attempt_partials(&mut output_rx),
+            vec![vec!["failed\n"], vec!["ok\n"]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pty_mode_runs_in_a_terminal() {
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+        let (_size_tx, size_rx) = watch::channel(TerminalSize { cols: 42, rows: 7 });
+
+        Command::run_and_send_output(
+            PaneKey::default(),
+            "test -t 1 && echo tty; stty size",
+            &ExecOptions {
+                pty: true,
+                terminal_size: size_rx,
+                ..Default::default()
+            },
+            output_tx,
+        )
+        .await
+        .unwrap();
+
+        let mut output = None;
+        while let Ok((_, event)) = output_rx.try_recv() {
+            if let CommandEvent::Output(out) = event {
+                output = Some(out);
+            }
+        }
+
+        assert_eq!(output.unwrap().stdout, "tty\n7 42\n");
+    }
+
+    #[tokio::test]
+    async fn test_pty_mode_timeout_kills_command() {
+        let (output_tx, _output_rx) = mpsc::channel(100);
+
+        let start = Instant::now();
+        let output = Command::run_and_send_output(
+            PaneKey::default(),
+            "echo started; sleep 10",
+            &ExecOptions {
+                pty: true,
+                timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            },
+            output_tx,
+        )
+        .await
+        .unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(output.timed_out);
+        assert_eq!(output.stdout, "started\n");
+    }
+
+    #[tokio::test]
+    async fn test_runs_with_configured_shell_cwd_and_env() {
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+
+        Command::run_and_send_output(
+            PaneKey::default(),
+            "echo $0 $PWD $GREETING",
+            &ExecOptions {
+                shell: Some("sh".to_string()),
+                cwd: Some("/".into()),
+                env: [("GREETING".to_string(), "hello".to_string())].into(),
+                ..Default::default()
+            },
+            output_tx,
+        )
+        .await
+        .unwrap();
+
+        let mut output = None;
+        while let Ok((_, event)) = output_rx.try_recv() {
+            if let CommandEvent::Output(out) = event {
+                output = Some(out);
+            }
+        }
+
+        assert_eq!(output.unwrap().stdout, "sh / hello\n");
+    }
+
+    #[tokio::test]
+    async fn test_direct_mode_execs_without_a_shell() {
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+        let options = ExecOptions {
+            exec_mode: ExecMode::Direct,
+            ..Default::default()
+        };
+
+        Command::run_and_send_output(
+            PaneKey::default(),
+            "printf '%s|' \"a b\" $HOME",
+            &options,
+            output_tx.clone(),
+        )
+        .await
+        .unwrap();
+        let result = Command::run_and_send_output(
+            PaneKey::default(),
+            "no-such-program",
+            &options,
+            output_tx,
+        )
+        .await;
+        assert!(result.is_err());
+
+        let mut outputs = Vec::new();
+        while let Ok((_, event)) = output_rx.try_recv() {
+            if let CommandEvent::Output(out) = event {
+                outputs.push(out);
+            }
+        }
+
+        assert_eq!(outputs[0].stdout, "a b|$HOME|");
+        assert_eq!(outputs[0].exit_status, Some(0));
+        assert!(outputs[1].stderr.starts_with("Failed to run command"));
+        assert_eq!(outputs[1].exit_status, None);
+    }
 }
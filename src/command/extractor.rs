@@ -0,0 +1,358 @@
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Picks the numbers to plot out of output that is more than a bare number.
+/// Each value found is named, so one output can feed several series.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Extractor {
+    /// The capture groups of the first match, or the whole match without
+    /// any, e.g. `/load: ([\d.]+)/`. With groups named `key` and `value`,
+    /// every match is a value, e.g. `/(?<key>\w+)=(?<value>[\d.]+)/`.
+    Regex(Regex),
+    /// A path into JSON output, e.g. `$.data[0].count` or `.data[0].count`.
+    /// A path to an object or array gives each of its numbers.
+    JsonPath {
+        source: String,
+        steps: Vec<PathStep>,
+    },
+    /// Whitespace separated columns of a line, all counted from 1, e.g.
+    /// `2:3` for the third column of the second line, `1:1,2,3` for the
+    /// first three, or `1:*` for all of them. Anything but plain digits
+    /// there, like `\d+:\d+`, is read as a regex.
+    Column {
+        line: usize,
+        /// `None` for every column.
+        columns: Option<Vec<usize>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathStep {
+    Key(String),
+    Index(usize),
+}
+
+impl Extractor {
+    /// The first number this extractor finds in `text`, if there is one.
+    pub fn extract(&self, text: &str) -> Option<f64> {
+        self.extract_all(text).into_iter().next().map(|(_, v)| v)
+    }
+
+    /// Every number this extractor finds in `text`, named and in the order
+    /// found. Unnamed regex groups and columns are named `$1`, `$2` and so on.
+    pub fn extract_all(&self, text: &str) -> Vec<(String, f64)> {
+        match self {
+            Extractor::Regex(re) => extract_regex(re, text),
+            Extractor::JsonPath { source, steps } => {
+                let Ok(json) = serde_json::from_str::<Value>(text) else {
+                    return Vec::new();
+                };
+                let found = steps.iter().try_fold(&json, |value, step| match step {
+                    PathStep::Key(key) => value.get(key),
+                    PathStep::Index(index) => value.get(index),
+                });
+                match found {
+                    Some(Value::Object(members)) => members
+                        .iter()
+                        .filter_map(|(key, v)| Some((key.clone(), json_number(v)?)))
+                        .collect(),
+                    Some(Value::Array(items)) => items
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, v)| Some((format!("[{}]", i), json_number(v)?)))
+                        .collect(),
+                    Some(v) => json_number(v)
+                        .map(|v| (source.clone(), v))
+                        .into_iter()
+                        .collect(),
+                    None => Vec::new(),
+                }
+            }
+            Extractor::Column { line, columns } => {
+                let Some(line) = line.checked_sub(1).and_then(|l| text.lines().nth(l)) else {
+                    return Vec::new();
+                };
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let wanted: Vec<usize> = match columns {
+                    Some(columns) => columns.clone(),
+                    None => (1..=fields.len()).collect(),
+                };
+                wanted
+                    .into_iter()
+                    .filter_map(|c| {
+                        let value = parse_number(fields.get(c.checked_sub(1)?)?)?;
+                        Some((format!("${}", c), value))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn extract_regex(re: &Regex, text: &str) -> Vec<(String, f64)> {
+    let names: Vec<Option<&str>> = re.capture_names().collect();
+
+    if names.contains(&Some("key")) && names.contains(&Some("value")) {
+        return re
+            .captures_iter(text)
+            .filter_map(|c| Some((c["key"].to_string(), parse_number(&c["value"])?)))
+            .collect();
+    }
+
+    let Some(captures) = re.captures(text) else {
+        return Vec::new();
+    };
+    if names.len() == 1 {
+        return parse_number(&captures[0])
+            .map(|v| ("$0".to_string(), v))
+            .into_iter()
+            .collect();
+    }
+    names
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter_map(|(i, name)| {
+            let value = parse_number(captures.get(i)?.as_str())?;
+            let name = name.map_or_else(|| format!("${}", i), str::to_string);
+            Some((name, value))
+        })
+        .collect()
+}
+
+fn json_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => parse_number(s),
+        _ => None,
+    }
+}
+
+/// Reads a number, ignoring surrounding space and a trailing `%`.
+fn parse_number(s: &str) -> Option<f64> {
+    let s = s.trim();
+    s.strip_suffix('%').unwrap_or(s).trim().parse().ok()
+}
+
+fn parse_path(path: &str) -> Result<Vec<PathStep>, String> {
+    let invalid = || format!("'{}' is not a valid JSON path", path);
+
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    let mut steps = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let (inner, after) = after.split_once(']').ok_or_else(invalid)?;
+            let inner = inner.trim();
+            let step = match inner.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let key = inner[1..]
+                        .strip_suffix(quote)
+                        .ok_or_else(|| format!("'{}' is missing a closing quote", path))?;
+                    PathStep::Key(key.to_string())
+                }
+                _ => PathStep::Index(inner.parse().map_err(|_| invalid())?),
+            };
+            steps.push(step);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                // A lone `.` is the whole document, like in jq.
+                if after.is_empty() {
+                    break;
+                }
+                return Err(invalid());
+            }
+            steps.push(PathStep::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    Ok(steps)
+}
+
+/// Only plain digits, so that a sign or anything else makes it a regex.
+fn parse_index(s: &str) -> Option<usize> {
+    let s = s.trim();
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// `*` for every column or a comma separated list, `None` when it's neither.
+fn parse_columns(s: &str) -> Option<Option<Vec<usize>>> {
+    match s.trim() {
+        "*" => Some(None),
+        s => s
+            .split(',')
+            .map(parse_index)
+            .collect::<Option<Vec<usize>>>()
+            .map(Some),
+    }
+}
+
+impl FromStr for Extractor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("an extractor can't be empty".to_string());
+        }
+
+        if s.starts_with('$') || s.starts_with('.') {
+            return Ok(Extractor::JsonPath {
+                source: s.to_string(),
+                steps: parse_path(s)?,
+            });
+        }
+
+        if let Some((line, columns)) = s.split_once(':') {
+            if let (Some(line), Some(columns)) = (parse_index(line), parse_columns(columns)) {
+                if line == 0 || columns.as_ref().is_some_and(|c| c.contains(&0)) {
+                    return Err("lines and columns are counted from 1".to_string());
+                }
+                return Ok(Extractor::Column { line, columns });
+            }
+        }
+
+        let pattern = s
+            .strip_prefix('/')
+            .and_then(|p| p.strip_suffix('/'))
+            .filter(|p| !p.is_empty())
+            .unwrap_or(s);
+        Regex::new(pattern)
+            .map(Extractor::Regex)
+            .map_err(|e| format!("invalid regex: {}", e))
+    }
+}
+
+impl fmt::Display for Extractor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Extractor::Regex(re) => write!(f, "/{}/", re.as_str()),
+            Extractor::JsonPath { source, .. } => write!(f, "{}", source),
+            Extractor::Column { line, columns } => match columns {
+                Some(columns) => {
+                    let columns: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+                    write!(f, "{}:{}", line, columns.join(","))
+                }
+                None => write!(f, "{}:*", line),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for Extractor {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Extractor> for String {
+    fn from(extractor: Extractor) -> Self {
+        extractor.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(extractor: &str, text: &str) -> Option<f64> {
+        extractor.parse::<Extractor>().unwrap().extract(text)
+    }
+
+    #[test]
+    fn test_regex_uses_first_group() {
+        assert_eq!(
+            extract("/load: ([\\d.]+)/", "cpu 4, load: 0.75"),
+            Some(0.75)
+        );
+        assert_eq!(extract("\\d+", "took 42ms"), Some(42.0));
+        assert_eq!(extract("/x=(\\d+)/", "y=1"), None);
+    }
+
+    #[test]
+    fn test_json_path() {
+        let json = r#"{"data": [{"count": 3}, {"count": "7"}], "a b": 1}"#;
+        assert_eq!(extract("$.data[0].count", json), Some(3.0));
+        assert_eq!(extract(".data[1].count", json), Some(7.0));
+        assert_eq!(extract("$['a b']", json), Some(1.0));
+        assert_eq!(extract(".", "12"), Some(12.0));
+        assert_eq!(extract("$.missing", json), None);
+        assert!("$.data[x]".parse::<Extractor>().is_err());
+    }
+
+    #[test]
+    fn test_quoted_key_needs_closing_quote() {
+        assert!("$[\"foo]".parse::<Extractor>().is_err());
+        assert!("$['foo\"]".parse::<Extractor>().is_err());
+        assert!("$[\"]".parse::<Extractor>().is_err());
+        assert_eq!(extract("$[\"foo\"]", r#"{"foo": 2}"#), Some(2.0));
+    }
+
+    #[test]
+    fn test_column_of_line() {
+        let df = "Filesystem Size Used Use%\n/dev/sda1 100G 42G 42%\n";
+        assert_eq!(extract("2:4", df), Some(42.0));
+        assert_eq!(extract("2:3", df), None);
+        assert_eq!(extract("3:1", df), None);
+        assert!("0:1".parse::<Extractor>().is_err());
+    }
+
+    #[test]
+    fn test_only_plain_integers_are_columns() {
+        let is_column = |s: &str| matches!(s.parse::<Extractor>(), Ok(Extractor::Column { .. }));
+        assert!(is_column("2:4"));
+        assert!(is_column("1:1,2,3"));
+        assert!(!is_column("+1:2"));
+        assert!(!is_column("1:+2"));
+        assert_eq!(extract("(\\d+):\\d+", "up 12:34"), Some(12.0));
+    }
+
+    #[test]
+    fn test_extracts_several_named_values() {
+        let all =
+            |extractor: &str, text: &str| extractor.parse::<Extractor>().unwrap().extract_all(text);
+        let named = |pairs: &[(&str, f64)]| -> Vec<(String, f64)> {
+            pairs.iter().map(|(n, v)| (n.to_string(), *v)).collect()
+        };
+
+        let loadavg = "0.52 0.58 0.59 1/389 12345";
+        assert_eq!(
+            all("1:1,2,3", loadavg),
+            named(&[("$1", 0.52), ("$2", 0.58), ("$3", 0.59)])
+        );
+        assert_eq!(all("1:*", loadavg).len(), 4);
+        assert_eq!(
+            all("/(?<key>\\w+)=(?<value>[\\d.]+)/", "rx=10 tx=2.5 state=up"),
+            named(&[("rx", 10.0), ("tx", 2.5)])
+        );
+        assert_eq!(
+            all("/(?<rx>\\d+) (\\d+)/", "5 7"),
+            named(&[("rx", 5.0), ("$2", 7.0)])
+        );
+        assert_eq!(
+            all("$.net", r#"{"net": {"rx": 1, "tx": "2", "if": "eth0"}}"#),
+            named(&[("rx", 1.0), ("tx", 2.0)])
+        );
+    }
+
+    #[test]
+    fn test_round_trips_through_string() {
+        for source in ["/load: (\\d+)/", "$.data[0].count", "2:3", "1:1,2", "1:*"] {
+            assert_eq!(source.parse::<Extractor>().unwrap().to_string(), source);
+        }
+    }
+}
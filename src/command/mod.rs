@@ -1,9 +1,25 @@
+mod backoff;
 mod command;
 mod executor;
+mod extractor;
+mod pool;
+mod pty;
 mod serialization;
 mod task_loop;
 mod task_manager;
+mod timetable;
+mod usage;
 
+pub use backoff::Backoff;
 pub use command::{
     Command, CommandControl, CommandEvent, CommandOutput, CommandSerializableState, CommandState,
+    ExecMode, ExecOptions, OutputStream, OutputView, PartialOutput, RetryPolicy, Schedule,
+    SchedulePolicy,
 };
+pub use executor::shell_program;
+pub use extractor::Extractor;
+pub use pool::ExecPool;
+pub use pty::TerminalSize;
+pub use serialization::naivedatetime_format;
+pub use timetable::Timetable;
+pub use usage::ResourceUsage;
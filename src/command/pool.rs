@@ -0,0 +1,112 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Caps how many commands run at once across all panes. Runs wait their turn
+/// in the order they asked for it, so a pane with a short interval can't
+/// starve the others.
+#[derive(Debug, Clone, Default)]
+pub struct ExecPool {
+    inner: Arc<PoolInner>,
+}
+
+#[derive(Debug, Default)]
+struct PoolInner {
+    /// `None` when runs aren't limited.
+    permits: Option<Semaphore>,
+    limit: Option<usize>,
+    queued: AtomicUsize,
+    running: AtomicUsize,
+}
+
+/// A slot in the pool, held for as long as the command runs.
+pub struct PoolPermit<'a> {
+    _permit: Option<SemaphorePermit<'a>>,
+    _running: Counted<'a>,
+}
+
+/// Counts itself in for as long as it's alive.
+struct Counted<'a>(&'a AtomicUsize);
+
+impl<'a> Counted<'a> {
+    fn new(count: &'a AtomicUsize) -> Self {
+        count.fetch_add(1, Ordering::Relaxed);
+        Self(count)
+    }
+}
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ExecPool {
+    /// A pool running at most `limit` commands at once, `None` or 0 for no limit.
+    pub fn new(limit: Option<usize>) -> Self {
+        let limit = limit.filter(|l| *l > 0);
+        Self {
+            inner: Arc::new(PoolInner {
+                permits: limit.map(Semaphore::new),
+                limit,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Waits for a free slot.
+    pub async fn acquire(&self) -> PoolPermit<'_> {
+        let permit = match &self.inner.permits {
+            Some(permits) => {
+                let _queued = Counted::new(&self.inner.queued);
+                // The semaphore is never closed.
+                permits.acquire().await.ok()
+            }
+            None => None,
+        };
+
+        PoolPermit {
+            _permit: permit,
+            _running: Counted::new(&self.inner.running),
+        }
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.inner.limit
+    }
+
+    pub fn queued(&self) -> usize {
+        self.inner.queued.load(Ordering::Relaxed)
+    }
+
+    pub fn running(&self) -> usize {
+        self.inner.running.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limits_and_counts_runs() {
+        let pool = ExecPool::new(Some(1));
+
+        let first = pool.acquire().await;
+        let waiting = tokio::spawn({
+            let pool = pool.clone();
+            async move {
+                let _second = pool.acquire().await;
+            }
+        });
+        while pool.queued() == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!((pool.running(), pool.queued()), (1, 1));
+
+        drop(first);
+        waiting.await.unwrap();
+        assert_eq!((pool.running(), pool.queued()), (0, 0));
+    }
+}
@@ -0,0 +1,124 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use tokio::io::unix::AsyncFd;
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Width and height of a pane's content area, in cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { cols: 80, rows: 24 }
+    }
+}
+
+impl From<TerminalSize> for libc::winsize {
+    fn from(size: TerminalSize) -> Self {
+        libc::winsize {
+            ws_row: size.rows,
+            ws_col: size.cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+}
+
+/// Opens a pseudo-terminal pair, returning `(master, slave)`.
+pub fn open(size: TerminalSize) -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut master = 0;
+    let mut slave = 0;
+    let winsize = libc::winsize::from(size);
+
+    let ret = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            &winsize,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+
+    // Keep plain `\n` line endings, the output is stored and diffed as text.
+    let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+    if unsafe { libc::tcgetattr(slave.as_raw_fd(), &mut termios) } == 0 {
+        termios.c_oflag &= !libc::ONLCR;
+        unsafe { libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &termios) };
+    }
+
+    Ok((master, slave))
+}
+
+pub fn resize(master: &impl AsRawFd, size: TerminalSize) -> io::Result<()> {
+    let winsize = libc::winsize::from(size);
+    if unsafe { libc::ioctl(master.as_raw_fd(), libc::TIOCSWINSZ, &winsize) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Makes the pty the controlling terminal of a freshly forked child.
+/// Runs between fork and exec, so it may only make async-signal-safe calls.
+pub fn attach_controlling_terminal() -> io::Result<()> {
+    if unsafe { libc::setsid() } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if unsafe { libc::ioctl(0, libc::TIOCSCTTY, 0) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Reads the master side of a pty, treating the `EIO` Linux returns once the
+/// last slave descriptor is closed as end of file. The master is read
+/// without blocking when the reactor says it's ready, so a dropped reader
+/// never leaves a thread waiting on a quiet pty.
+pub struct PtyReader(AsyncFd<File>);
+
+impl PtyReader {
+    pub fn new(master: OwnedFd) -> io::Result<Self> {
+        let flags = unsafe { libc::fcntl(master.as_raw_fd(), libc::F_GETFL) };
+        if flags < 0
+            || unsafe { libc::fcntl(master.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) }
+                < 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self(AsyncFd::new(File::from(master))?))
+    }
+}
+
+impl AsyncRead for PtyReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = ready!(self.0.poll_read_ready(cx))?;
+            let unfilled = buf.initialize_unfilled();
+            match guard.try_io(|file| file.get_ref().read(unfilled)) {
+                Ok(Ok(read)) => {
+                    buf.advance(read);
+                    return Poll::Ready(Ok(()));
+                }
+                Ok(Err(e)) if e.raw_os_error() == Some(libc::EIO) => return Poll::Ready(Ok(())),
+                Ok(Err(e)) => return Poll::Ready(Err(e)),
+                // Not readable after all, wait for the next wakeup.
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
@@ -22,3 +22,27 @@ pub mod naivedatetime_format {
         NaiveDateTime::parse_from_str(s.trim(), FORMAT).map_err(serde::de::Error::custom)
     }
 }
+
+pub mod optional_naivedatetime_format {
+    use super::*;
+
+    pub fn serialize<S>(date: &Option<NaiveDateTime>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match date {
+            Some(date) => naivedatetime_format::serialize(date, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| NaiveDateTime::parse_from_str(s.trim(), FORMAT))
+            .transpose()
+            .map_err(serde::de::Error::custom)
+    }
+}
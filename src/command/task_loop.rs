@@ -1,29 +1,114 @@
 use std::time::Duration;
 
+use chrono::Local;
 use tokio::sync::mpsc;
-use tokio::time::{self, MissedTickBehavior};
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant};
 
-use super::{Command, CommandControl, CommandState};
+use super::{
+    Command, CommandControl, CommandOutput, CommandState, ExecOptions, Schedule, SchedulePolicy,
+};
 use crate::command::CommandEvent;
 use crate::logging::{debug, info, warn};
 use crate::pane::PaneKey;
 
+const TIMETABLE_TOLERANCE: Duration = Duration::from_millis(500);
+
+/// The in-flight run, aborted when dropped so stopping or replacing the
+/// pane's task also ends the command it's running.
+struct Run(JoinHandle<Option<CommandOutput>>);
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl Schedule {
+    /// The interval to wait after `quiet_runs` failed or unchanged runs in a row.
+    fn interval(&self, quiet_runs: u32) -> Duration {
+        match &self.backoff {
+            Some(backoff) => backoff.interval(self.interval, quiet_runs),
+            None => self.interval,
+        }
+    }
+
+    /// The longer interval the pane is backed off to, if any.
+    fn backed_off(&self, quiet_runs: u32) -> Option<Duration> {
+        let interval = self.interval(quiet_runs);
+        (self.timetable.is_none() && interval != self.interval).then_some(interval)
+    }
+
+    /// When the run following one due at `after` should start.
+    fn next_after(&self, after: Instant, quiet_runs: u32) -> Instant {
+        if let Some(timetable) = &self.timetable {
+            // Look from a moment ahead, the timer may fire just before the
+            // wall clock reaches the slot it was set for.
+            let from = Local::now() + TIMETABLE_TOLERANCE;
+            return match timetable.next_after(from) {
+                Some(next) => Instant::now() + (next - Local::now()).to_std().unwrap_or_default(),
+                None => after + Duration::from_secs(u32::MAX as u64),
+            };
+        }
+
+        let interval = self.interval(quiet_runs);
+        match self.policy {
+            SchedulePolicy::FixedRate => {
+                let next = Instant::now() + until_wall_clock_multiple(interval);
+                // Guard against landing on the slot that just fired.
+                if next < after + interval / 2 {
+                    next + interval
+                } else {
+                    next
+                }
+            }
+            SchedulePolicy::FixedDelay | SchedulePolicy::SkipIfRunning => after + interval,
+        }
+    }
+}
+
+/// Time left until the local wall clock is next a whole multiple of `interval`,
+/// so a 1m interval runs on every :00 second.
+fn until_wall_clock_multiple(interval: Duration) -> Duration {
+    let period = interval.as_nanos();
+    if period == 0 {
+        return Duration::ZERO;
+    }
+
+    let now = Local::now();
+    let local_nanos = (now.timestamp() + now.offset().local_minus_utc() as i64) as u128
+        * 1_000_000_000
+        + now.timestamp_subsec_nanos() as u128;
+
+    Duration::from_nanos((period - local_nanos % period) as u64)
+}
+
 impl Command {
     pub async fn run_command_task(
         id: PaneKey,
         exec: String,
-        interval: Duration,
+        mut schedule: Schedule,
+        mut options: ExecOptions,
         state: CommandState,
         mut control_rx: mpsc::Receiver<CommandControl>,
-        output_tx: tokio::sync::mpsc::Sender<(PaneKey, CommandEvent)>,
+        output_tx: mpsc::Sender<(PaneKey, CommandEvent)>,
     ) {
-        let interval_duration = interval;
-        let mut tick_interval = time::interval(interval);
-        tick_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
-
         let mut is_paused = matches!(state, CommandState::Paused);
 
+        // `None` while a fixed-delay run is in progress, it is scheduled once the run ends.
+        let mut next_run = Some(Instant::now());
+        let mut running: Option<Run> = None;
+        // A run came due while the previous one was still going.
+        let mut pending = false;
+
+        // Failed or unchanged runs in a row, which the backoff slows down on.
+        let mut quiet_runs = 0;
+        let mut last_output: Option<CommandOutput> = None;
+        let mut backed_off = None;
+
         loop {
+            let mut due = false;
+
             tokio::select! {
                 Some(control) = control_rx.recv() => {
                     match control {
@@ -32,42 +117,211 @@ impl Command {
                             break;
                         }
                         CommandControl::IntervalSet(duration) => {
-                            info!("Pane {:?} task received set interval.", id);
-                            tick_interval = time::interval(duration);
-                            tick_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
                             info!("Pane {:?} interval set to {:?}", id, duration);
+                            schedule.interval = duration;
+                            if next_run.is_some() {
+                                next_run = Some(Instant::now());
+                            }
+                        }
+                        CommandControl::SetSchedulePolicy(policy) => {
+                            info!("Pane {:?} schedule policy set to {}", id, policy);
+                            schedule.policy = policy;
+                            if next_run.is_some() {
+                                next_run = Some(schedule.next_after(Instant::now(), quiet_runs));
+                            }
+                        }
+                        CommandControl::SetBackoff(backoff) => {
+                            info!("Pane {:?} backoff set to {:?}", id, backoff);
+                            schedule.backoff = backoff;
+                            if next_run.is_some() {
+                                next_run = Some(schedule.next_after(Instant::now(), quiet_runs));
+                            }
+                        }
+                        CommandControl::SetPty(enabled) => {
+                            info!("Pane {:?} pty mode set to {}", id, enabled);
+                            options.pty = enabled;
                         }
-
                         CommandControl::Pause => { info!("Pane {:?} paused", id); is_paused = true; }
                         CommandControl::Resume => {
                             info!("Pane {:?} resumed", id);
                             is_paused = false;
-                            if let Err(e) = Self::run_and_send_output(id, &exec, output_tx.clone()).await {
-                                warn!("Pane {:?} resume execution failed: {}", id, e);
-                            }
-                            tick_interval.reset_at(time::Instant::now() + interval_duration);
+                            due = true;
                         }
                         CommandControl::Execute => {
                             info!("Pane {:?} received ad-hoc execution command.", id);
-                            if let Err(e) = Self::run_and_send_output(id, &exec, output_tx.clone()).await {
-                                warn!("Pane {:?} ad-hoc execution failed: {}", id, e);
-                            }
-                            tick_interval.reset_at(time::Instant::now() + interval_duration);
+                            due = true;
                         }
                         _ => ()
                     }
+                    if due && schedule.policy != SchedulePolicy::FixedDelay {
+                        next_run = Some(schedule.next_after(Instant::now(), quiet_runs));
+                    }
                 }
-                _ = tick_interval.tick(), if !is_paused => {
-                    debug!("Pane {:?} task running command: {}", id, exec);
-                    if let Err(e) = Self::run_and_send_output(id, &exec, output_tx.clone()).await {
+                _ = time::sleep_until(next_run.unwrap_or_else(Instant::now)), if !is_paused && next_run.is_some() => {
+                    let deadline = next_run.unwrap_or_else(Instant::now);
+                    due = true;
+                    if schedule.policy != SchedulePolicy::FixedDelay {
+                        next_run = Some(schedule.next_after(deadline, quiet_runs));
+                    }
+                }
+                outcome = Self::finished(&mut running) => {
+                    running = None;
+                    let quiet = match &outcome {
+                        Some(output) => {
+                            output.exit_status != Some(0)
+                                || last_output.as_ref().is_some_and(|last| last.same_output(output))
+                        }
+                        None => true,
+                    };
+                    quiet_runs = if quiet { quiet_runs.saturating_add(1) } else { 0 };
+                    if outcome.is_some() {
+                        last_output = outcome;
+                    }
+
+                    if pending {
+                        pending = false;
+                        due = true;
+                    } else if schedule.policy == SchedulePolicy::FixedDelay {
+                        next_run = Some(schedule.next_after(Instant::now(), quiet_runs));
+                    }
+                }
+            }
+
+            if schedule.backed_off(quiet_runs) != backed_off {
+                backed_off = schedule.backed_off(quiet_runs);
+                debug!("Pane {:?} backed off to {:?}", id, backed_off);
+                // Runs not waiting on the last one to finish were already
+                // scheduled with the old interval.
+                if schedule.policy != SchedulePolicy::FixedDelay && next_run.is_some() {
+                    next_run = Some(schedule.next_after(Instant::now(), quiet_runs));
+                }
+                if let Err(e) = output_tx
+                    .send((id, CommandEvent::Backoff(backed_off)))
+                    .await
+                {
+                    warn!("Failed to send backoff for pane {:?}: {}", id, e);
+                }
+            }
+
+            if !due {
+                continue;
+            }
+
+            if running.as_ref().is_some_and(|run| !run.0.is_finished()) {
+                if schedule.policy == SchedulePolicy::SkipIfRunning {
+                    debug!("Pane {:?} skipping run, previous one is still going", id);
+                } else {
+                    pending = true;
+                }
+                continue;
+            }
+
+            debug!("Pane {:?} task running command: {}", id, exec);
+            if schedule.policy == SchedulePolicy::FixedDelay {
+                next_run = None;
+            }
+            let (exec, options, output_tx) = (exec.clone(), options.clone(), output_tx.clone());
+            running = Some(Run(tokio::spawn(async move {
+                match Self::run_and_send_output(id, &exec, &options, output_tx).await {
+                    Ok(output) => Some(output),
+                    Err(e) => {
                         warn!("Pane {:?} task failed to run command: {}", id, e);
+                        None
                     }
                 }
-                else => {
-                    // Necessary to prevent panics if Paused
-                    tokio::task::yield_now().await;
+            })));
+        }
+    }
+
+    /// Waits for the in-flight run, if there is one, and returns its output.
+    async fn finished(running: &mut Option<Run>) -> Option<CommandOutput> {
+        match running {
+            Some(run) => (&mut run.0).await.unwrap_or_else(|e| {
+                warn!("Command run task failed: {}", e);
+                None
+            }),
+            None => std::future::pending().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fixed_delay_waits_after_each_run() {
+        let (_control_tx, control_rx) = mpsc::channel(1);
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+        let interval = Duration::from_millis(100);
+
+        let task = tokio::spawn(Command::run_command_task(
+            PaneKey::default(),
+            "sleep 0.2".to_string(),
+            Schedule {
+                interval,
+                policy: SchedulePolicy::FixedDelay,
+                timetable: None,
+                backoff: None,
+            },
+            ExecOptions::default(),
+            CommandState::Idle,
+            control_rx,
+            output_tx,
+        ));
+
+        let mut finished_at = None;
+        let mut starts = 0;
+        while starts < 3 {
+            match output_rx.recv().await.unwrap().1 {
+                CommandEvent::Started => {
+                    if let Some(finished) = finished_at.take() {
+                        assert!(Instant::now() - finished >= interval * 9 / 10);
+                    }
+                    starts += 1;
                 }
+                CommandEvent::Output(_) => finished_at = Some(Instant::now()),
+                CommandEvent::Partial(..) | CommandEvent::Backoff(_) => {}
             }
         }
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_stop_kills_the_running_command() {
+        let (control_tx, control_rx) = mpsc::channel(1);
+        let (output_tx, mut output_rx) = mpsc::channel(100);
+
+        // The background sleep is in the shell's process group.
+        let task = tokio::spawn(Command::run_command_task(
+            PaneKey::default(),
+            "sleep 30 & echo $!; wait".to_string(),
+            Schedule {
+                interval: Duration::from_secs(60),
+                policy: SchedulePolicy::FixedDelay,
+                timetable: None,
+                backoff: None,
+            },
+            ExecOptions::default(),
+            CommandState::Idle,
+            control_rx,
+            output_tx,
+        ));
+
+        let pid = loop {
+            if let CommandEvent::Partial(_, chunk) = output_rx.recv().await.unwrap().1 {
+                break chunk.trim().to_string();
+            }
+        };
+        control_tx.send(CommandControl::Stop).await.unwrap();
+        task.await.unwrap();
+
+        // Gone, or a zombie waiting to be reaped.
+        let stat = format!("/proc/{}/stat", pid);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while std::fs::read_to_string(&stat).is_ok_and(|s| !s.contains(") Z ")) {
+            assert!(Instant::now() < deadline, "sleep {} is still running", pid);
+            time::sleep(Duration::from_millis(20)).await;
+        }
     }
 }
@@ -1,38 +1,97 @@
+use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
+use crate::alert::{AlertRule, Hooks};
 use crate::command::{
     Command, CommandControl, CommandEvent, CommandOutput, CommandSerializableState, CommandState,
+    ExecOptions, ExecPool, OutputStream, OutputView, PartialOutput, Schedule, TerminalSize,
 };
+use crate::config::utils::MIN_INTERVAL;
+use crate::config::AppConfig;
 use crate::logging::{info, warn};
 use crate::pane::PaneKey;
-use crate::ui::DisplayType;
+use crate::ui::{ChartView, DisplayType, Pipeline, Series};
 
 impl Command {
     pub fn spawn(
         id: PaneKey,
         exec: String,
-        display: DisplayType,
-        interval: Duration,
+        config: &AppConfig,
+        pool: &ExecPool,
         output_tx: mpsc::Sender<(PaneKey, CommandEvent)>,
     ) -> Self {
         let (control_tx, control_rx) = mpsc::channel(1);
+        let (terminal_size, size_rx) = watch::channel(TerminalSize::default());
+
+        let schedule = Schedule {
+            interval: config.interval,
+            policy: config.schedule_policy,
+            timetable: config.timetable.clone(),
+            backoff: config.backoff,
+        };
+        let options = ExecOptions {
+            timeout: config.timeout,
+            retry: config.retry,
+            exec_mode: config.exec_mode,
+            shell: config.shell.clone(),
+            cwd: config.cwd.clone(),
+            env: config.env.clone(),
+            pty: false,
+            terminal_size: size_rx,
+            pool: pool.clone(),
+        };
 
         let cmd = exec.clone();
         let task_handle = tokio::spawn(async move {
-            Command::run_command_task(id, cmd, interval, CommandState::Idle, control_rx, output_tx)
-                .await;
+            Command::run_command_task(
+                id,
+                cmd,
+                schedule,
+                options,
+                CommandState::Idle,
+                control_rx,
+                output_tx,
+            )
+            .await;
         });
 
         info!("Adding new command: {}", &exec);
         Command {
             exec,
-            interval,
+            interval: config.interval,
+            schedule_policy: config.schedule_policy,
+            timetable: config.timetable.clone(),
+            backoff: config.backoff,
+            backed_off_interval: None,
+            timeout: config.timeout,
+            retry: config.retry,
+            exec_mode: config.exec_mode,
+            shell: config.shell.clone(),
+            cwd: config.cwd.clone(),
+            env: config.env.clone(),
             output_history: VecDeque::new(),
             state: CommandState::Idle,
-            display_type: display,
+            display_type: config.default_display,
+            output_view: OutputView::default(),
+            series: Series::default(),
+            extractor: None,
+            transforms: Pipeline::default(),
+            secondary_axis: false,
+            chart_view: ChartView::default(),
+            alerts: config.alerts.clone(),
+            alerts_held: vec![None; config.alerts.len()],
+            flash_until: None,
+            hooks: Hooks::default(),
+            strip_ansi: false,
+            dedup_history: config.dedup_history,
+            pty: false,
+            terminal_size,
+            partial_output: PartialOutput::default(),
+            output_generation: 0,
+            formatted: RefCell::new(None),
             task_handle: Some(task_handle),
             control_tx,
         }
@@ -40,12 +99,13 @@ impl Command {
 
     pub fn restore_tasks(
         tasks_state: HashMap<PaneKey, CommandSerializableState>,
+        pool: &ExecPool,
         output_tx: mpsc::Sender<(PaneKey, CommandEvent)>,
     ) -> HashMap<PaneKey, Command> {
         let mut running_tasks = HashMap::new();
 
         for (id, state) in tasks_state {
-            let new_cmd = Self::spawn_from_state(id, state, output_tx.clone());
+            let new_cmd = Self::spawn_from_state(id, state, pool, output_tx.clone());
 
             info!("Restarting command for id: {:?}", id);
             running_tasks.insert(id, new_cmd);
@@ -57,24 +117,72 @@ impl Command {
     pub fn spawn_from_state(
         id: PaneKey,
         state: CommandSerializableState,
+        pool: &ExecPool,
         output_tx: mpsc::Sender<(PaneKey, CommandEvent)>,
     ) -> Command {
         let (control_tx, control_rx) = mpsc::channel(1);
+        let (terminal_size, size_rx) = watch::channel(TerminalSize::default());
 
-        let interval = state.interval;
+        let schedule = Schedule {
+            interval: state.interval,
+            policy: state.schedule_policy,
+            timetable: state.timetable.clone(),
+            backoff: state.backoff,
+        };
+        let options = ExecOptions {
+            timeout: state.timeout,
+            retry: state.retry,
+            exec_mode: state.exec_mode,
+            shell: state.shell.clone(),
+            cwd: state.cwd.clone(),
+            env: state.env.clone(),
+            pty: state.pty,
+            terminal_size: size_rx,
+            pool: pool.clone(),
+        };
         let exec = state.exec.clone();
         let cmd_state = state.state;
 
         let task_handle = tokio::spawn(async move {
-            Self::run_command_task(id, exec, interval, cmd_state, control_rx, output_tx).await;
+            Self::run_command_task(
+                id, exec, schedule, options, cmd_state, control_rx, output_tx,
+            )
+            .await;
         });
 
         Command {
             exec: state.exec,
             interval: state.interval,
+            schedule_policy: state.schedule_policy,
+            timetable: state.timetable,
+            backoff: state.backoff,
+            backed_off_interval: None,
+            timeout: state.timeout,
+            retry: state.retry,
+            exec_mode: state.exec_mode,
+            shell: state.shell,
+            cwd: state.cwd,
+            env: state.env,
             output_history: state.output_history,
             state: state.state,
             display_type: state.display_type,
+            output_view: state.output_view,
+            series: state.series,
+            extractor: state.extractor,
+            transforms: state.transforms,
+            secondary_axis: state.secondary_axis,
+            chart_view: ChartView::default(),
+            alerts_held: vec![None; state.alerts.len()],
+            alerts: state.alerts,
+            flash_until: None,
+            hooks: state.hooks,
+            strip_ansi: state.strip_ansi,
+            dedup_history: state.dedup_history,
+            pty: state.pty,
+            terminal_size,
+            partial_output: PartialOutput::default(),
+            output_generation: 0,
+            formatted: RefCell::new(None),
             task_handle: Some(task_handle),
             control_tx,
         }
@@ -93,10 +201,10 @@ impl Command {
             CommandControl::IntervalDecrease => {
                 if self.interval > Duration::from_secs(1) {
                     self.interval -= Duration::from_secs(1);
-                } else if self.interval > Duration::from_secs_f64(0.1) {
-                    self.interval -= Duration::from_secs_f64(0.1);
+                } else if self.interval > MIN_INTERVAL {
+                    self.interval = self.interval.saturating_sub(MIN_INTERVAL).max(MIN_INTERVAL);
                 } else {
-                    self.interval = Duration::from_secs_f64(0.1);
+                    self.interval = MIN_INTERVAL;
                 }
                 CommandControl::IntervalSet(self.interval)
             }
@@ -107,6 +215,10 @@ impl Command {
             CommandControl::Pause => self.state = CommandState::Paused,
             CommandControl::Resume => self.state = CommandState::Idle,
             CommandControl::Stop => self.state = CommandState::Stopped,
+            CommandControl::IntervalSet(interval) => self.interval = *interval,
+            CommandControl::SetPty(pty) => self.pty = *pty,
+            CommandControl::SetSchedulePolicy(policy) => self.schedule_policy = *policy,
+            CommandControl::SetBackoff(backoff) => self.backoff = *backoff,
             _ => {}
         }
 
@@ -118,7 +230,22 @@ impl Command {
         }
     }
 
-    pub fn record_output(&mut self, new_output: CommandOutput, max_history: usize) {
+    /// Adds `new_output` to the history. With `dedup_history` a run with the same
+    /// output and exit status as the last one is merged into it instead,
+    /// counting the repeat, so the history only holds changes.
+    pub fn record_output(&mut self, mut new_output: CommandOutput, max_history: usize) {
+        self.partial_output.clear();
+        self.output_generation += 1;
+        if let Some(last) = self.output_history.back_mut().filter(|last| {
+            self.dedup_history
+                && last.same_output(&new_output)
+                && last.exit_status == new_output.exit_status
+        }) {
+            new_output.repeats = last.repeats + 1;
+            new_output.first_seen = Some(last.first_seen.unwrap_or(last.time));
+            *last = new_output;
+            return;
+        }
         if self.output_history.len() >= max_history {
             self.output_history.pop_front();
         }
@@ -129,7 +256,112 @@ impl Command {
         self.output_history.back()
     }
 
+    /// Marks the command as running, clearing the last run's live output.
+    pub fn start_run(&mut self) {
+        self.state = CommandState::Executing;
+        self.partial_output.clear();
+        self.output_generation += 1;
+    }
+
+    pub fn append_partial(&mut self, stream: OutputStream, chunk: &str) {
+        self.partial_output.push(stream, chunk);
+        self.output_generation += 1;
+    }
+
+    /// Output received so far from a running command, if any.
+    pub fn live_output(&self) -> Option<&PartialOutput> {
+        if self.state == CommandState::Executing && !self.partial_output.is_empty() {
+            Some(&self.partial_output)
+        } else {
+            None
+        }
+    }
+
     pub fn update_display(&mut self, display: DisplayType) {
         self.display_type = display;
     }
+
+    pub fn cycle_output_view(&mut self) {
+        self.output_view = self.output_view.next();
+    }
+
+    pub fn cycle_series(&mut self) {
+        self.series = self.series.next();
+    }
+
+    /// The named values charts and sparklines plot for a history entry,
+    /// several when the extractor finds more than one.
+    pub fn series_values(&self, out: &CommandOutput) -> Vec<(String, f64)> {
+        match (&self.extractor, self.series) {
+            (Some(extractor), Series::Value) => extractor.extract_all(&out.text(self.output_view)),
+            _ => self
+                .series
+                .value(out, self.output_view, self.extractor.as_ref())
+                .map(|v| (self.series.to_string(), v))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// History entries a chart shows across the pane before zooming.
+    pub fn chart_fit(&self) -> usize {
+        self.terminal_size.borrow().cols as usize
+    }
+
+    pub fn pan_chart(&mut self, older: bool) {
+        let (len, fit) = (self.output_history.len(), self.chart_fit());
+        self.chart_view.pan(older, len, fit);
+    }
+
+    pub fn zoom_chart(&mut self, zoom_in: bool) {
+        let (len, fit) = (self.output_history.len(), self.chart_fit());
+        self.chart_view.zoom(zoom_in, len, fit);
+    }
+
+    /// The alerts that fire on `out`, which is about to be recorded.
+    pub fn fired_alerts(&mut self, out: &CommandOutput) -> Vec<AlertRule> {
+        let value = self.series_values(out).first().map(|(_, v)| *v);
+        let previous = self.output_history.back();
+
+        let mut fired = Vec::new();
+        for (rule, held) in self.alerts.iter().zip(self.alerts_held.iter_mut()) {
+            let holds = rule.condition.holds(previous, out, value);
+            if rule.condition.fires(holds, *held) {
+                fired.push(rule.clone());
+            }
+            *held = Some(holds);
+        }
+        fired
+    }
+
+    pub fn add_alert(&mut self, rule: AlertRule) {
+        self.alerts.push(rule);
+        self.alerts_held.push(None);
+    }
+
+    pub fn clear_alerts(&mut self) {
+        self.alerts.clear();
+        self.alerts_held.clear();
+    }
+
+    pub fn toggle_secondary_axis(&mut self) {
+        self.secondary_axis = !self.secondary_axis;
+    }
+
+    pub fn toggle_ansi(&mut self) {
+        self.strip_ansi = !self.strip_ansi;
+    }
+
+    pub fn toggle_dedup_history(&mut self) {
+        self.dedup_history = !self.dedup_history;
+    }
+
+    /// Records the pane's content size, resizing the pty of a running command.
+    pub fn resize_terminal(&self, size: TerminalSize) {
+        self.terminal_size.send_if_modified(|current| {
+            let changed = *current != size;
+            *current = size;
+            changed
+        });
+    }
 }
@@ -0,0 +1,332 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Timelike,
+};
+use serde::{Deserialize, Serialize};
+
+/// How far ahead to look for a matching time before giving up, so
+/// expressions like `0 0 31 2 *` can't loop forever.
+const SEARCH_LIMIT_DAYS: i64 = 366 * 5;
+
+/// Wall clock times a pane runs at, used instead of its interval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Timetable {
+    /// A five field cron expression: minute, hour, day of month, month and
+    /// day of week.
+    Cron(CronExpr),
+    /// Times of day, e.g. `09:00,12:30,17:00`.
+    Times(Vec<NaiveTime>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpr {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl Timetable {
+    /// The first time strictly after `after` this timetable fires.
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Timetable::Cron(cron) => cron.next_after(after.naive_local()),
+            Timetable::Times(times) => {
+                let now = after.naive_local();
+                (0..=1)
+                    .flat_map(|day| {
+                        let date = now.date() + ChronoDuration::days(day);
+                        times.iter().map(move |t| date.and_time(*t))
+                    })
+                    .filter(|t| *t > now)
+                    .find_map(|t| Local.from_local_datetime(&t).earliest())
+            }
+        }
+    }
+}
+
+impl CronExpr {
+    fn next_after(&self, after: NaiveDateTime) -> Option<DateTime<Local>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        let limit = start + ChronoDuration::days(SEARCH_LIMIT_DAYS);
+        let mut t = start;
+
+        while t < limit {
+            if !has(self.months, t.month()) {
+                t = first_of_next_month(t.date())?.and_time(NaiveTime::MIN);
+                continue;
+            }
+            if !self.matches_day(t.date()) {
+                t = (t.date() + ChronoDuration::days(1)).and_time(NaiveTime::MIN);
+                continue;
+            }
+            if !has(self.hours, t.hour()) {
+                t = t.with_minute(0)? + ChronoDuration::hours(1);
+                continue;
+            }
+            if !has(self.minutes, t.minute()) {
+                t += ChronoDuration::minutes(1);
+                continue;
+            }
+
+            // Times skipped by a DST change don't exist locally, move on.
+            match Local.from_local_datetime(&t).earliest() {
+                Some(found) => return Some(found),
+                None => t += ChronoDuration::minutes(1),
+            }
+        }
+        None
+    }
+
+    /// Like cron, a day matches either field when both are restricted.
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+fn has(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDate> {
+    match date.month() {
+        12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+        m => NaiveDate::from_ymd_opt(date.year(), m + 1, 1),
+    }
+}
+
+/// Parses one cron field like `*`, `*/15`, `1-5` or `0,30` into a bit mask.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("step can't be zero".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (parse_value(a, min, max)?, parse_value(b, min, max)?),
+                // `5/10` means from 5 to the end in steps of 10.
+                None if step > 1 => (parse_value(range, min, max)?, max),
+                None => {
+                    let v = parse_value(range, min, max)?;
+                    (v, v)
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("invalid range '{}'", range));
+        }
+
+        for v in (start..=end).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+
+    Ok(mask)
+}
+
+fn parse_value(value: &str, min: u32, max: u32) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(v) if (min..=max).contains(&v) => Ok(v),
+        _ => Err(format!(
+            "'{}' is not a number from {} to {}",
+            value, min, max
+        )),
+    }
+}
+
+impl FromStr for CronExpr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("expected 5 cron fields, got {}", fields.len()));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        // Both 0 and 7 are Sunday.
+        if has(weekdays, 7) {
+            weekdays |= 1;
+        }
+
+        Ok(CronExpr {
+            source: fields.join(" "),
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+}
+
+impl FromStr for Timetable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if !s.contains(':') {
+            return s.parse().map(Timetable::Cron);
+        }
+
+        let mut times = s
+            .split(',')
+            .map(|t| {
+                let t = t.trim();
+                NaiveTime::parse_from_str(t, "%H:%M:%S")
+                    .or_else(|_| NaiveTime::parse_from_str(t, "%H:%M"))
+                    .map_err(|_| format!("'{}' is not a cron expression or a list of times", s))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        times.sort();
+        times.dedup();
+
+        Ok(Timetable::Times(times))
+    }
+}
+
+impl fmt::Display for Timetable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Timetable::Cron(cron) => write!(f, "{}", cron.source),
+            Timetable::Times(times) => {
+                let times: Vec<String> = times
+                    .iter()
+                    .map(|t| match t.second() {
+                        0 => t.format("%H:%M").to_string(),
+                        _ => t.format("%H:%M:%S").to_string(),
+                    })
+                    .collect();
+                write!(f, "{}", times.join(","))
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for Timetable {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Timetable> for String {
+    fn from(timetable: Timetable) -> Self {
+        timetable.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(s: &str) -> DateTime<Local> {
+        let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        Local.from_local_datetime(&naive).unwrap()
+    }
+
+    fn next(timetable: &str, after: &str) -> String {
+        let timetable: Timetable = timetable.parse().unwrap();
+        timetable
+            .next_after(local(after))
+            .unwrap()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+
+    #[test]
+    fn test_cron_every_quarter_hour() {
+        assert_eq!(
+            next("*/15 * * * *", "2024-01-01 10:07:30"),
+            "2024-01-01 10:15:00"
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2024-01-01 10:45:00"),
+            "2024-01-01 11:00:00"
+        );
+    }
+
+    #[test]
+    fn test_cron_business_hours() {
+        // 2024-01-05 is a Friday.
+        assert_eq!(
+            next("0 9-17 * * 1-5", "2024-01-05 17:30:00"),
+            "2024-01-08 09:00:00"
+        );
+    }
+
+    #[test]
+    fn test_cron_day_or_weekday() {
+        // The 15th, or any Sunday.
+        assert_eq!(
+            next("0 0 15 * 0", "2024-01-01 00:00:00"),
+            "2024-01-07 00:00:00"
+        );
+    }
+
+    #[test]
+    fn test_times_wrap_to_next_day() {
+        assert_eq!(
+            next("17:00, 09:30", "2024-01-01 12:00:00"),
+            "2024-01-01 17:00:00"
+        );
+        assert_eq!(
+            next("17:00, 09:30", "2024-01-01 17:00:00"),
+            "2024-01-02 09:30:00"
+        );
+    }
+
+    #[test]
+    fn test_invalid_timetables() {
+        assert!("61 * * * *".parse::<Timetable>().is_err());
+        assert!("*/0 * * * *".parse::<Timetable>().is_err());
+        assert!("25:00".parse::<Timetable>().is_err());
+        assert!("* * *".parse::<Timetable>().is_err());
+    }
+
+    #[test]
+    fn test_round_trips_as_string() {
+        let timetable: Timetable = "9:00,17:30:15".parse().unwrap();
+        assert_eq!(timetable.to_string(), "09:00,17:30:15");
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Pane {
+            timetable: Timetable,
+        }
+
+        let pane = Pane { timetable };
+        let saved = toml::to_string(&pane).unwrap();
+        assert_eq!(saved, "timetable = \"09:00,17:30:15\"\n");
+        assert_eq!(toml::from_str::<Pane>(&saved).unwrap(), pane);
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::utils::{deserialize_duration, serialize_duration};
+
+/// `ru_maxrss` is in kilobytes everywhere but macOS, which reports bytes.
+#[cfg(target_os = "macos")]
+const MAX_RSS_UNIT: u64 = 1;
+#[cfg(not(target_os = "macos"))]
+const MAX_RSS_UNIT: u64 = 1024;
+
+/// What a run cost, from the `rusage` its process was reaped with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub struct ResourceUsage {
+    #[serde(
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub user_time: Duration,
+    #[serde(
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub system_time: Duration,
+    /// Peak resident set size, in bytes.
+    pub max_rss: u64,
+    pub stdout_bytes: u64,
+    pub stderr_bytes: u64,
+}
+
+impl ResourceUsage {
+    pub fn new(usage: &libc::rusage, stdout_bytes: u64, stderr_bytes: u64) -> Self {
+        Self {
+            user_time: timeval_to_duration(usage.ru_utime),
+            system_time: timeval_to_duration(usage.ru_stime),
+            max_rss: (usage.ru_maxrss.max(0) as u64).saturating_mul(MAX_RSS_UNIT),
+            stdout_bytes,
+            stderr_bytes,
+        }
+    }
+
+    pub fn cpu_time(&self) -> Duration {
+        self.user_time + self.system_time
+    }
+}
+
+fn timeval_to_duration(tv: libc::timeval) -> Duration {
+    Duration::from_secs(tv.tv_sec.max(0) as u64) + Duration::from_micros(tv.tv_usec.max(0) as u64)
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -11,26 +11,66 @@ use figment::{
 };
 use serde::{Deserialize, Serialize};
 
-use super::utils::{app_name, deserialize_duration, get_home_dir, serialize_duration};
+use super::utils::{
+    app_name, deserialize_interval, deserialize_optional_duration, get_home_dir,
+    serialize_duration, serialize_optional_duration,
+};
+use crate::alert::{AlertRule, Hooks};
+use crate::command::{Backoff, ExecMode, RetryPolicy, SchedulePolicy, Timetable};
 use crate::controls::{actions::Action, KeyMode};
+use crate::history::Retention;
+use crate::ui::utils::formatting::format_alerts;
 use crate::{config::theme::Theme, ui::DisplayType};
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(default)]
 pub struct AppConfig {
     #[serde(
-        deserialize_with = "deserialize_duration",
+        deserialize_with = "deserialize_interval",
         serialize_with = "serialize_duration"
     )]
     pub interval: Duration,
+    pub schedule_policy: SchedulePolicy,
+    /// Run at these wall clock times instead of every `interval`.
+    pub timetable: Option<Timetable>,
+    /// Slow panes down while their command fails or prints the same output.
+    pub backoff: Option<Backoff>,
+    #[serde(
+        deserialize_with = "deserialize_optional_duration",
+        serialize_with = "serialize_optional_duration"
+    )]
+    pub timeout: Option<Duration>,
+    /// Retry failed runs before recording them.
+    pub retry: RetryPolicy,
+    pub exec_mode: ExecMode,
+    /// Shell commands run with, `$SHELL` or `sh` when unset.
+    pub shell: Option<String>,
+    pub cwd: Option<PathBuf>,
+    pub env: BTreeMap<String, String>,
     pub beep: bool,
+    /// Alert rules every new pane starts with.
+    pub alerts: Vec<AlertRule>,
+    /// Run on output events of panes without hooks of their own.
+    pub hooks: Hooks,
     pub err_exit: bool,
     pub chg_exit: bool,
+    pub chg_exit_pane: Option<usize>,
     pub wrap: bool,
     pub default_display: DisplayType,
     pub max_history: usize,
+    /// Merge runs whose output didn't change into one history entry, so
+    /// `max_history` holds changes. New panes start with this, each can
+    /// toggle it.
+    pub dedup_history: bool,
+    /// Also keep every run on disk under `history_dir`, to browse in observe
+    /// mode past the `max_history` kept in memory.
+    pub store_history: bool,
+    pub history_retention: Retention,
+    /// Most commands running at once across all panes, unlimited when unset.
+    pub max_concurrency: Option<usize>,
     pub zen: bool,
     pub sessions_dir: PathBuf,
+    pub history_dir: PathBuf,
     pub snapshot_dir: PathBuf,
     pub logs_dir: PathBuf,
     pub log_level: Option<String>,
@@ -42,12 +82,48 @@ impl fmt::Display for AppConfig {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Configuration loaded successfully:")?;
         writeln!(f, "  Interval: {:?}", self.interval)?;
+        writeln!(f, "  Schedule Policy: {}", self.schedule_policy)?;
+        writeln!(
+            f,
+            "  Backoff: {}",
+            self.backoff.map_or("N/A".to_string(), |b| b.to_string())
+        )?;
+        writeln!(
+            f,
+            "  Timetable: {}",
+            self.timetable
+                .as_ref()
+                .map_or("N/A".to_string(), |t| t.to_string())
+        )?;
+        writeln!(f, "  Timeout: {:?}", self.timeout)?;
+        writeln!(f, "  Retry: {}", self.retry)?;
+        writeln!(f, "  Exec Mode: {}", self.exec_mode)?;
+        writeln!(f, "  Shell: {}", self.shell.as_deref().unwrap_or("N/A"))?;
+        writeln!(f, "  Working Directory: {:?}", self.cwd)?;
+        writeln!(f, "  Environment: {:?}", self.env)?;
         writeln!(f, "  Beep: {}", self.beep)?;
+        writeln!(f, "  Alerts: {}", format_alerts(&self.alerts))?;
+        writeln!(f, "  Hooks: {}", self.hooks)?;
         writeln!(f, "  Exit on Error: {}", self.err_exit)?;
         writeln!(f, "  Exit on Change: {}", self.chg_exit)?;
+        writeln!(
+            f,
+            "  Exit on Change Pane: {}",
+            self.chg_exit_pane
+                .map_or("Any".to_string(), |id| id.to_string())
+        )?;
         writeln!(f, "  Wrap: {}", self.wrap)?;
         writeln!(f, "  Default Display: {:?}", self.default_display)?;
         writeln!(f, "  Max History: {}", self.max_history)?;
+        writeln!(f, "  Dedup History: {}", self.dedup_history)?;
+        writeln!(f, "  Store History: {}", self.store_history)?;
+        writeln!(f, "  History Retention: {}", self.history_retention)?;
+        writeln!(
+            f,
+            "  Max Concurrency: {}",
+            self.max_concurrency
+                .map_or("N/A".to_string(), |n| n.to_string())
+        )?;
         writeln!(f, "  Zen: {}", self.zen)?;
         writeln!(
             f,
@@ -56,9 +132,12 @@ impl fmt::Display for AppConfig {
         )?;
         writeln!(f, "  Logs Directory: {:?}", self.logs_dir)?;
         writeln!(f, "  Sessions Directory: {:?}", self.sessions_dir)?;
+        writeln!(f, "  History Directory: {:?}", self.history_dir)?;
         writeln!(f, "  Snapshot Directory: {:?}", self.snapshot_dir)?;
 
         writeln!(f, "  Theme")?;
+        writeln!(f, "    Chart precision: {}", self.theme.chart_precision)?;
+        writeln!(f, "    Chart time labels: {}", self.theme.chart_time_labels)?;
         writeln!(f, "    Collapse borders: {}", self.theme.collapse_borders)?;
         writeln!(f, "    Show display type: {}", self.theme.show_display_type)?;
         writeln!(
@@ -102,6 +181,20 @@ impl AppConfig {
             self.beep = true;
         }
 
+        self.alerts.extend(cli.alerts.iter().cloned());
+
+        if let Some(hook) = &cli.on_change {
+            self.hooks.on_change = Some(hook.clone());
+        }
+
+        if let Some(hook) = &cli.on_fail {
+            self.hooks.on_fail = Some(hook.clone());
+        }
+
+        if let Some(hook) = &cli.on_recover {
+            self.hooks.on_recover = Some(hook.clone());
+        }
+
         if cli.err_exit {
             self.err_exit = true;
         }
@@ -110,6 +203,15 @@ impl AppConfig {
             self.chg_exit = true;
         }
 
+        if let Some(pane_id) = cli.chg_exit_pane {
+            self.chg_exit = true;
+            self.chg_exit_pane = Some(pane_id);
+        }
+
+        if cli.exec {
+            self.exec_mode = ExecMode::Direct;
+        }
+
         if cli.zen {
             self.zen = true;
         }
@@ -126,8 +228,40 @@ impl AppConfig {
             self.max_history = max_history;
         }
 
+        if cli.dedup_history {
+            self.dedup_history = true;
+        }
+
+        if cli.store_history {
+            self.store_history = true;
+        }
+
         if let Some(interval) = cli.interval {
-            self.interval = Duration::from_secs(interval);
+            self.interval = interval;
+        }
+
+        if let Some(timetable) = &cli.at {
+            self.timetable = Some(timetable.clone());
+        }
+
+        if cli.backoff && self.backoff.is_none() {
+            self.backoff = Some(Backoff::default());
+        }
+
+        if cli.precise {
+            self.schedule_policy = SchedulePolicy::FixedRate;
+        }
+
+        if let Some(timeout) = cli.timeout {
+            self.timeout = Some(timeout);
+        }
+
+        if let Some(retries) = cli.retries {
+            self.retry.retries = retries;
+        }
+
+        if let Some(delay) = cli.retry_delay {
+            self.retry.delay = delay;
         }
 
         if cli.verbose.is_present() {
@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 use std::time::Duration;
 
 use crokey::{key, KeyCombination};
@@ -6,24 +7,48 @@ use ratatui::style::{Color, Modifier, Style};
 
 use super::utils::{app_name, default_sessions_dir_path, default_snapshot_dir_path};
 use super::AppConfig;
-use crate::config::theme::{Palette, Theme};
-use crate::config::utils::default_logging_dir_path;
+use crate::alert::Hooks;
+use crate::command::{Backoff, ExecMode, RetryPolicy, SchedulePolicy};
+use crate::config::theme::{Palette, Theme, TimeLabels};
+use crate::config::utils::{default_history_dir_path, default_logging_dir_path};
 use crate::controls::actions::Action;
 use crate::controls::KeyMode;
+use crate::history::Retention;
 use crate::ui::DisplayType;
 
 // GENERAL SETTINGS
+const BACKOFF: Option<Backoff> = None;
 const BEEP: bool = false;
+const CWD: Option<PathBuf> = None;
+const DEDUP_HISTORY: bool = false;
 const DEFAULT_DISPLAY: DisplayType = DisplayType::RawText;
 const EXIT_ON_CHANGE: bool = false;
+const EXIT_ON_CHANGE_PANE: Option<usize> = None;
 const EXIT_ON_ERROR: bool = false;
+const EXEC_MODE: ExecMode = ExecMode::Shell;
+const HISTORY_RETENTION: Retention = Retention {
+    max_runs: Some(100_000),
+    max_age: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+    max_bytes: Some(64 << 20),
+};
 const INTERVAL_SECS: u64 = 5;
 const LOG_LEVEL: Option<String> = None;
+const MAX_CONCURRENCY: Option<usize> = None;
 const MAX_HISTORY: usize = 10;
+const RETRY: RetryPolicy = RetryPolicy {
+    retries: 0,
+    delay: Duration::from_secs(1),
+};
+const SCHEDULE_POLICY: SchedulePolicy = SchedulePolicy::FixedDelay;
+const SHELL: Option<String> = None;
+const STORE_HISTORY: bool = false;
+const TIMEOUT: Option<Duration> = None;
 const WRAP: bool = true;
 const ZEN: bool = false;
 
 // THEME
+const CHART_PRECISION: usize = 2;
+const CHART_TIME_LABELS: TimeLabels = TimeLabels::Clock;
 const COLLAPSE_BORDERS: bool = false;
 const SHOW_STATE: bool = true;
 const SHOW_LAST_UPDATED: bool = true;
@@ -54,25 +79,42 @@ pub fn default_keybindings() -> HashMap<KeyMode, HashMap<KeyCombination, Action>
     map.insert(
         KeyMode::Normal,
         HashMap::from([
+            (key!(a), Action::AnsiToggle),
+            (key!(b), Action::BackoffToggle),
             (key!(c), Action::EnterCmdMode),
+            (key!('['), Action::ChartPanBack),
+            (key!(']'), Action::ChartPanForward),
+            (key!('}'), Action::ChartZoomIn),
+            (key!('{'), Action::ChartZoomOut),
+            (key!(shift - a), Action::EnterAlertEditMode),
             (key!(shift - d), Action::EnterDisplaySelectMode),
+            (key!(shift - e), Action::EnterExtractorEditMode),
             (key!('?'), Action::EnterHelpMode),
+            (key!(shift - h), Action::EnterHookEditMode),
+            (key!(n), Action::EnterIntervalEditMode),
             (key!(o), Action::EnterObserveMode),
             (key!(shift - l), Action::EnterSessionLoadMode),
             (key!(shift - s), Action::EnterSessionSaveMode),
+            (key!(shift - t), Action::EnterTransformEditMode),
             (key!(space), Action::Execute),
             (key!(d), Action::IntervalDecrease),
             (key!(i), Action::IntervalIncrease),
             (key!(x), Action::KillPane),
             (key!(l), Action::LoadLatestSession),
+            (key!(e), Action::OutputViewCycle),
             (key!('<'), Action::PaneDecreaseHorizontal),
             (key!('-'), Action::PaneDecreaseVertical),
             (key!('>'), Action::PaneIncreaseHorizontal),
             (key!('+'), Action::PaneIncreaseVertical),
             (key!(tab), Action::Cycle),
+            (key!(u), Action::DedupHistoryToggle),
             (key!(p), Action::Pause),
+            (key!(t), Action::PtyToggle),
             (key!(r), Action::Resume),
             (key!(s), Action::SaveSession),
+            (key!(shift - p), Action::SchedulePolicyCycle),
+            (key!(y), Action::SecondaryAxisToggle),
+            (key!(g), Action::SeriesCycle),
             (key!(h), Action::SplitHorizontal),
             (key!(v), Action::SplitVertical),
             (key!(w), Action::WrapToggle),
@@ -92,6 +134,9 @@ pub fn default_keybindings() -> HashMap<KeyMode, HashMap<KeyCombination, Action>
     // SESSION SAVE MODE BINDINGS
     map.insert(KeyMode::SessionSave, HashMap::new());
 
+    // PROMPT BINDINGS
+    map.insert(KeyMode::Prompt, HashMap::new());
+
     // DISPLAY SELECT BINDINGS
     map.insert(KeyMode::DisplayTypeSelect, HashMap::new());
 
@@ -122,11 +167,22 @@ impl Default for Palette {
     fn default() -> Self {
         Self {
             border_active: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            border_alert: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
             border_inactive: Style::default().fg(Color::DarkGray),
             border_label: Style::default().fg(Color::Reset).bg(Color::Rgb(40, 40, 40)),
             chart_bar: Style::default().fg(Color::LightBlue),
             chart_line: Style::default().fg(Color::LightMagenta),
             chart_scatter: Style::default().fg(Color::LightGreen),
+            chart_series: vec![
+                Style::default().fg(Color::LightMagenta),
+                Style::default().fg(Color::LightGreen),
+                Style::default().fg(Color::LightBlue),
+                Style::default().fg(Color::LightYellow),
+                Style::default().fg(Color::LightCyan),
+                Style::default().fg(Color::LightRed),
+            ],
             counter_key: Style::default().fg(Color::LightGreen).bold(),
             diff_add: Style::default().fg(Color::Green).bg(Color::Rgb(20, 40, 20)),
             diff_remove: Style::default().fg(Color::Red).bg(Color::Rgb(40, 20, 20)),
@@ -144,12 +200,15 @@ impl Default for Palette {
             scroll_track: Style::default().fg(Color::Blue),
             search_match: Style::default().fg(Color::Black).bg(Color::Yellow),
             spark_line: Style::default().fg(Color::LightBlue),
+            stderr: Style::default().fg(Color::LightYellow),
         }
     }
 }
 impl Default for Theme {
     fn default() -> Self {
         Theme {
+            chart_precision: CHART_PRECISION,
+            chart_time_labels: CHART_TIME_LABELS,
             collapse_borders: COLLAPSE_BORDERS,
             show_state: SHOW_STATE,
             show_last_updated: SHOW_LAST_UPDATED,
@@ -170,16 +229,33 @@ impl Default for AppConfig {
 
         AppConfig {
             interval: Duration::from_secs(INTERVAL_SECS),
+            schedule_policy: SCHEDULE_POLICY,
+            backoff: BACKOFF,
+            timetable: None,
+            timeout: TIMEOUT,
+            retry: RETRY,
+            exec_mode: EXEC_MODE,
+            shell: SHELL,
+            cwd: CWD,
+            env: BTreeMap::new(),
             zen: ZEN,
             beep: BEEP,
+            alerts: Vec::new(),
+            hooks: Hooks::default(),
             err_exit: EXIT_ON_ERROR,
             chg_exit: EXIT_ON_CHANGE,
+            chg_exit_pane: EXIT_ON_CHANGE_PANE,
             wrap: WRAP,
             default_display: DEFAULT_DISPLAY,
             log_level: LOG_LEVEL,
             max_history: MAX_HISTORY,
+            dedup_history: DEDUP_HISTORY,
+            store_history: STORE_HISTORY,
+            history_retention: HISTORY_RETENTION,
+            max_concurrency: MAX_CONCURRENCY,
             logs_dir: default_logging_dir_path(&proj_dirs),
             sessions_dir: default_sessions_dir_path(&proj_dirs),
+            history_dir: default_history_dir_path(&proj_dirs),
             snapshot_dir: default_snapshot_dir_path(&proj_dirs),
             keybindings: default_keybindings(),
             theme: Theme::default(),
@@ -1,8 +1,13 @@
+use std::fmt;
+
 use ratatui::style::Style;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Theme {
+    /// Most decimals shown in chart axis labels.
+    pub chart_precision: usize,
+    pub chart_time_labels: TimeLabels,
     pub collapse_borders: bool,
     pub palette: Palette,
     pub show_display_type: bool,
@@ -13,14 +18,38 @@ pub struct Theme {
     pub show_status_bar: bool,
 }
 
+/// How chart x-axis labels show when each run happened.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeLabels {
+    /// Time of day, e.g. `14:05:30`.
+    #[default]
+    Clock,
+    /// How long ago, e.g. `-5m30s`.
+    Relative,
+}
+
+impl fmt::Display for TimeLabels {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeLabels::Clock => write!(f, "clock"),
+            TimeLabels::Relative => write!(f, "relative"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Palette {
     pub border_active: Style,
+    /// Border of a pane flashed by an alert.
+    pub border_alert: Style,
     pub border_inactive: Style,
     pub border_label: Style,
     pub chart_bar: Style,
     pub chart_line: Style,
     pub chart_scatter: Style,
+    /// Colors of the series of a chart plotting more than one.
+    pub chart_series: Vec<Style>,
     pub counter_key: Style,
     pub diff_add: Style,
     pub diff_remove: Style,
@@ -38,4 +67,5 @@ pub struct Palette {
     pub scroll_track: Style,
     pub search_match: Style,
     pub spark_line: Style,
+    pub stderr: Style,
 }
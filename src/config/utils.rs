@@ -1,7 +1,10 @@
+use std::fmt;
 use std::path::PathBuf;
 use std::time::Duration;
 
 use directories::ProjectDirs;
+use humantime::format_duration;
+use serde::de::{self, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serializer};
 
 pub fn app_name() -> &'static str {
@@ -38,6 +41,19 @@ pub fn default_snapshot_dir_path(proj_dirs: &Option<ProjectDirs>) -> PathBuf {
     }
 }
 
+pub fn default_history_dir_path(proj_dirs: &Option<ProjectDirs>) -> PathBuf {
+    #[cfg(target_os = "macos")]
+    if let Some(home_dir) = get_home_dir() {
+        return home_dir.join(".config").join(app_name()).join("history");
+    }
+
+    if let Some(dirs) = proj_dirs {
+        dirs.data_dir().join("history")
+    } else {
+        PathBuf::from("./data/history")
+    }
+}
+
 pub fn default_logging_dir_path(proj_dirs: &Option<ProjectDirs>) -> PathBuf {
     #[cfg(target_os = "macos")]
     if let Some(home_dir) = get_home_dir() {
@@ -51,17 +67,196 @@ pub fn default_logging_dir_path(proj_dirs: &Option<ProjectDirs>) -> PathBuf {
     }
 }
 
+/// Parses a duration given as seconds, which may be fractional, or as a
+/// humantime string like "250ms" or "1m30s".
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let parsed = match s.parse::<f64>() {
+        Ok(secs) => Duration::try_from_secs_f64(secs).map_err(|e| e.to_string()),
+        Err(_) => humantime::parse_duration(s).map_err(|e| e.to_string()),
+    };
+    parsed.map_err(|e| format!("invalid duration '{}': {}", s, e))
+}
+
+/// The shortest interval between runs, a shorter one would keep a CPU busy
+/// spawning the command.
+pub const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Parses an interval between runs like [`parse_duration`], rejecting one
+/// under [`MIN_INTERVAL`].
+pub fn parse_interval(s: &str) -> Result<Duration, String> {
+    parse_duration(s).and_then(check_interval)
+}
+
+fn check_interval(interval: Duration) -> Result<Duration, String> {
+    if interval < MIN_INTERVAL {
+        Err(format!(
+            "an interval must be at least {}",
+            format_duration(MIN_INTERVAL)
+        ))
+    } else {
+        Ok(interval)
+    }
+}
+
+/// Accepts whole or fractional seconds, humantime strings, and the
+/// `{ secs, nanos }` tables older sessions were saved with.
+struct DurationVisitor;
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "seconds or a duration like \"250ms\" or \"1m30s\"")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Duration, E> {
+        Ok(Duration::from_secs(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Duration, E> {
+        u64::try_from(v)
+            .map(Duration::from_secs)
+            .map_err(|_| E::custom("a duration can't be negative"))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Duration, E> {
+        Duration::try_from_secs_f64(v).map_err(E::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Duration, E> {
+        parse_duration(v).map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Duration, A::Error> {
+        let (mut secs, mut nanos) = (0, 0);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "secs" => secs = map.next_value()?,
+                "nanos" => nanos = map.next_value()?,
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(Duration::new(secs, nanos))
+    }
+}
+
+struct DurationValue(Duration);
+
+impl<'de> Deserialize<'de> for DurationValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(DurationVisitor)
+            .map(DurationValue)
+    }
+}
+
 pub fn deserialize_duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let secs = u64::deserialize(deserializer)?;
-    Ok(Duration::from_secs(secs))
+    deserializer.deserialize_any(DurationVisitor)
+}
+
+/// Deserializes an interval between runs, which like
+/// [`parse_interval`] can't be under [`MIN_INTERVAL`].
+pub fn deserialize_interval<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_duration(deserializer).and_then(|d| check_interval(d).map_err(de::Error::custom))
 }
 
 pub fn serialize_duration<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    serializer.serialize_u64(duration.as_secs())
+    serializer.serialize_str(&format_duration(*duration).to_string())
+}
+
+pub fn deserialize_optional_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let duration = Option::<DurationValue>::deserialize(deserializer)?;
+    Ok(duration.map(|d| d.0))
+}
+
+pub fn serialize_optional_duration<S>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match duration {
+        Some(duration) => serializer.serialize_some(&format_duration(*duration).to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Pane {
+        #[serde(
+            deserialize_with = "deserialize_interval",
+            serialize_with = "serialize_duration"
+        )]
+        interval: Duration,
+    }
+
+    fn interval(toml_value: &str) -> Result<Duration, toml::de::Error> {
+        toml::from_str::<Pane>(&format!("interval = {}", toml_value)).map(|p| p.interval)
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("0.2"), Ok(Duration::from_millis(200)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1m30s"), Ok(Duration::from_secs(90)));
+        assert!(parse_duration("-1").is_err());
+        assert!(parse_duration("soon").is_err());
+        // Fine for a timeout or delay, but not between runs.
+        assert_eq!(parse_duration("0"), Ok(Duration::ZERO));
+        assert!(parse_interval("0").is_err());
+        assert!(parse_interval("0ms").is_err());
+        assert!(parse_interval("50ms").is_err());
+        assert_eq!(parse_interval("0.1"), Ok(MIN_INTERVAL));
+    }
+
+    #[test]
+    fn test_deserialize_duration_forms() {
+        assert_eq!(interval("5").unwrap(), Duration::from_secs(5));
+        assert_eq!(interval("0.5").unwrap(), Duration::from_millis(500));
+        assert_eq!(interval("\"1m 30s\"").unwrap(), Duration::from_secs(90));
+        assert_eq!(
+            interval("{ secs = 2, nanos = 500000000 }").unwrap(),
+            Duration::from_millis(2500)
+        );
+        assert!(interval("-3").is_err());
+        assert!(interval("0").is_err());
+        assert!(interval("0.0").is_err());
+        assert!(interval("\"0ms\"").is_err());
+    }
+
+    #[test]
+    fn test_serialized_duration_round_trips() {
+        let pane = Pane {
+            interval: Duration::from_millis(1500),
+        };
+        let saved = toml::to_string(&pane).unwrap();
+
+        assert_eq!(saved, "interval = \"1s 500ms\"\n");
+        assert_eq!(
+            toml::from_str::<Pane>(&saved).unwrap().interval,
+            pane.interval
+        );
+    }
 }
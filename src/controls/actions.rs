@@ -2,14 +2,26 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Action {
+    AnsiToggle,
+    BackoffToggle,
+    ChartPanBack,
+    ChartPanForward,
+    ChartZoomIn,
+    ChartZoomOut,
     Confirm,
     Cycle,
+    DedupHistoryToggle,
     EnterCmdMode,
+    EnterAlertEditMode,
     EnterDisplaySelectMode,
+    EnterExtractorEditMode,
     EnterHelpMode,
+    EnterHookEditMode,
+    EnterIntervalEditMode,
     EnterObserveMode,
     EnterSessionLoadMode,
     EnterSessionSaveMode,
+    EnterTransformEditMode,
     Escape,
     Execute,
     IntervalDecrease,
@@ -20,14 +32,19 @@ pub enum Action {
     MoveLeft,
     MoveRight,
     MoveUp,
+    OutputViewCycle,
     PaneDecreaseHorizontal,
     PaneDecreaseVertical,
     PaneIncreaseHorizontal,
     PaneIncreaseVertical,
     Pause,
+    PtyToggle,
     Quit,
     Resume,
     SaveSession,
+    SchedulePolicyCycle,
+    SecondaryAxisToggle,
+    SeriesCycle,
     Search,
     ScrollTop,
     ScrollBottom,
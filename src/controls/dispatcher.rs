@@ -6,6 +6,7 @@ use super::display_select_mode::handle_display_type_select_keys;
 use super::edit_mode::handle_editing_mode_keys;
 use super::normal_mode::handle_normal_mode_keys;
 use super::observe_mode::handle_observe_mode_keys;
+use super::prompt_mode::handle_prompt_keys;
 use super::session_load_mode::handle_session_load_keys;
 use super::session_save_mode::handle_session_save_keys;
 use crate::app::App;
@@ -22,6 +23,8 @@ pub async fn handle_event(app: &mut App, event: Event) -> io::Result<()> {
 
         AppMode::SessionSave { .. } => handle_session_save_keys(app, event).await?,
 
+        AppMode::Prompt(_) => handle_prompt_keys(app, event).await?,
+
         AppMode::DisplayTypeSelect { .. } => handle_display_type_select_keys(app, event).await?,
 
         AppMode::Help { .. } => handle_help_keys(app, event).await?,
@@ -7,6 +7,7 @@ pub enum KeyMode {
     CmdEdit,
     SessionLoad,
     SessionSave,
+    Prompt,
     Observe,
     DisplayTypeSelect,
     Help,
@@ -20,6 +21,7 @@ impl fmt::Display for KeyMode {
             KeyMode::CmdEdit => write!(f, "CmdEdit"),
             KeyMode::SessionLoad => write!(f, "SessionLoad"),
             KeyMode::SessionSave => write!(f, "SessionSave"),
+            KeyMode::Prompt => write!(f, "Prompt"),
             KeyMode::Observe => write!(f, "Observe"),
             KeyMode::DisplayTypeSelect => write!(f, "DisplayTypeSelect"),
             KeyMode::Help => write!(f, "Help"),
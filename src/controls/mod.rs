@@ -9,5 +9,6 @@ mod help_mode;
 mod key_modes;
 mod normal_mode;
 mod observe_mode;
+mod prompt_mode;
 mod session_load_mode;
 mod session_save_mode;
@@ -109,6 +109,52 @@ pub async fn handle_normal_mode_keys(app: &mut App, event: Event) -> io::Result<
                     warn!("Failed to send AppControl::SendControl: {}", e);
                 }
             }
+            Action::PtyToggle => {
+                let id = app.pane_manager.active_pane_id;
+                let pty = app.tasks.get(&id).is_some_and(|c| !c.pty);
+                if let Err(e) = app
+                    .app_control_tx
+                    .send(AppControl::SendControl(id, CommandControl::SetPty(pty)))
+                    .await
+                {
+                    warn!("Failed to send AppControl::SendControl: {}", e);
+                }
+            }
+            Action::BackoffToggle => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get(&id) {
+                    let backoff = match command.backoff {
+                        Some(_) => None,
+                        None => Some(app.config.backoff.unwrap_or_default()),
+                    };
+                    if let Err(e) = app
+                        .app_control_tx
+                        .send(AppControl::SendControl(
+                            id,
+                            CommandControl::SetBackoff(backoff),
+                        ))
+                        .await
+                    {
+                        warn!("Failed to send AppControl::SendControl: {}", e);
+                    }
+                }
+            }
+            Action::SchedulePolicyCycle => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get(&id) {
+                    let policy = command.schedule_policy.next();
+                    if let Err(e) = app
+                        .app_control_tx
+                        .send(AppControl::SendControl(
+                            id,
+                            CommandControl::SetSchedulePolicy(policy),
+                        ))
+                        .await
+                    {
+                        warn!("Failed to send AppControl::SendControl: {}", e);
+                    }
+                }
+            }
             Action::IntervalIncrease => {
                 let id = app.pane_manager.active_pane_id;
                 if let Err(e) = app
@@ -159,6 +205,36 @@ pub async fn handle_normal_mode_keys(app: &mut App, event: Event) -> io::Result<
                 info!("Saving sessions mode");
                 app.mode = AppMode::new_session_save();
             }
+            Action::EnterIntervalEditMode => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get(&id) {
+                    app.mode = AppMode::new_interval_edit(command.interval);
+                }
+            }
+            Action::EnterAlertEditMode => {
+                let id = app.pane_manager.active_pane_id;
+                if app.tasks.contains_key(&id) {
+                    app.mode = AppMode::new_alert_edit();
+                }
+            }
+            Action::EnterHookEditMode => {
+                let id = app.pane_manager.active_pane_id;
+                if app.tasks.contains_key(&id) {
+                    app.mode = AppMode::new_hook_edit();
+                }
+            }
+            Action::EnterExtractorEditMode => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get(&id) {
+                    app.mode = AppMode::new_extractor_edit(command.extractor.as_ref());
+                }
+            }
+            Action::EnterTransformEditMode => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get(&id) {
+                    app.mode = AppMode::new_transform_edit(&command.transforms);
+                }
+            }
             Action::EnterDisplaySelectMode => {
                 info!("Display select mode");
                 app.mode = AppMode::new_display_type_select();
@@ -183,6 +259,48 @@ pub async fn handle_normal_mode_keys(app: &mut App, event: Event) -> io::Result<
                 info!("Observe mode");
                 app.mode = AppMode::new_observing(&app);
             }
+            Action::AnsiToggle => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get_mut(&id) {
+                    command.toggle_ansi();
+                }
+            }
+            Action::DedupHistoryToggle => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get_mut(&id) {
+                    command.toggle_dedup_history();
+                }
+            }
+            Action::OutputViewCycle => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get_mut(&id) {
+                    command.cycle_output_view();
+                }
+            }
+            Action::SeriesCycle => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get_mut(&id) {
+                    command.cycle_series();
+                }
+            }
+            Action::ChartPanBack | Action::ChartPanForward => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get_mut(&id) {
+                    command.pan_chart(*act == Action::ChartPanBack);
+                }
+            }
+            Action::ChartZoomIn | Action::ChartZoomOut => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get_mut(&id) {
+                    command.zoom_chart(*act == Action::ChartZoomIn);
+                }
+            }
+            Action::SecondaryAxisToggle => {
+                let id = app.pane_manager.active_pane_id;
+                if let Some(command) = app.tasks.get_mut(&id) {
+                    command.toggle_secondary_axis();
+                }
+            }
             Action::WrapToggle => {
                 app.config.wrap = !app.config.wrap;
             }
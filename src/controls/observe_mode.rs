@@ -20,6 +20,7 @@ pub async fn handle_observe_mode_keys(app: &mut App, event: Event) -> io::Result
         focus,
         scroll_offset,
         max_scroll,
+        stored,
         ..
     } = &mut app.mode
     else {
@@ -74,8 +75,11 @@ pub async fn handle_observe_mode_keys(app: &mut App, event: Event) -> io::Result
                 ObserveFocus::History => {
                     let active_id = app.pane_manager.active_pane_id;
                     let cmd = app.tasks.get(&active_id).unwrap();
+                    let len = stored
+                        .as_ref()
+                        .map_or(cmd.output_history.len(), |s| s.len());
 
-                    if *selected_history_idx < cmd.output_history.len().saturating_sub(1) {
+                    if *selected_history_idx < len.saturating_sub(1) {
                         *selected_history_idx += 1;
                         *scroll_offset = 0;
                     }
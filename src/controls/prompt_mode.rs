@@ -0,0 +1,61 @@
+use std::io;
+use std::mem;
+
+use crokey::crossterm::event::{self, Event};
+use crokey::KeyCombination;
+use tui_input::backend::crossterm::EventHandler;
+
+use crate::app::App;
+use crate::controls::actions::Action;
+use crate::controls::KeyMode;
+use crate::mode::AppMode;
+
+pub async fn handle_prompt_keys(app: &mut App, event: Event) -> io::Result<()> {
+    let current_context: KeyMode = app.mode.key_mode();
+
+    let AppMode::Prompt(prompt) = &mut app.mode else {
+        return Ok(());
+    };
+
+    let Event::Key(key_event) = event else {
+        return Ok(());
+    };
+    if key_event.kind != event::KeyEventKind::Press {
+        return Ok(());
+    }
+
+    let key_comb: KeyCombination = KeyCombination::from(key_event);
+
+    let action = app
+        .config
+        .keybindings
+        .get(&current_context)
+        .and_then(|map| map.get(&key_comb))
+        .or_else(|| {
+            app.config
+                .keybindings
+                .get(&KeyMode::Global)
+                .and_then(|map| map.get(&key_comb))
+        });
+
+    match action {
+        Some(Action::Confirm) => {
+            // The prompt is taken out of the app so it can be applied to it.
+            let AppMode::Prompt(mut prompt) = mem::take(&mut app.mode) else {
+                return Ok(());
+            };
+            if let Err(e) = prompt.confirm(app) {
+                prompt.error = Some(e);
+                app.mode = AppMode::Prompt(prompt);
+            }
+        }
+        Some(Action::Escape) => {
+            app.mode = AppMode::Normal;
+        }
+        _ => {
+            prompt.input.handle_event(&event);
+            prompt.error = None;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+
+use chrono::NaiveDateTime;
+
+use super::store::Stamp;
+use crate::command::CommandOutput;
+use crate::logging::warn;
+
+/// Runs read back at a time; observe mode shows one and the one before it.
+const CACHED_RUNS: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+struct StoredRun {
+    offset: u64,
+    len: u64,
+    time: NaiveDateTime,
+}
+
+/// Where a pane's stored runs are in its history file, oldest first. Runs
+/// are only read when they're shown.
+#[derive(Debug)]
+pub struct StoredHistory {
+    path: PathBuf,
+    runs: Vec<StoredRun>,
+    /// The file indexed, which compacting replaces.
+    inode: u64,
+    /// How far into the file runs have been indexed.
+    scanned: u64,
+    cache: Vec<(usize, CommandOutput)>,
+}
+
+impl StoredHistory {
+    pub fn open(path: PathBuf) -> Self {
+        let mut history = Self {
+            path,
+            runs: Vec::new(),
+            inode: 0,
+            scanned: 0,
+            cache: Vec::new(),
+        };
+        history.refresh();
+        history
+    }
+
+    /// Indexes runs added since the last look, starting over when the file
+    /// was compacted in the meantime.
+    pub fn refresh(&mut self) {
+        if let Err(e) = self.scan() {
+            warn!("Failed to read history {:?}: {}", self.path, e);
+        }
+    }
+
+    fn scan(&mut self) -> io::Result<()> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        let metadata = file.metadata()?;
+        if metadata.ino() != self.inode || metadata.len() < self.scanned {
+            self.runs.clear();
+            self.cache.clear();
+            self.inode = metadata.ino();
+            self.scanned = 0;
+        }
+        if metadata.len() == self.scanned {
+            return Ok(());
+        }
+
+        file.seek(SeekFrom::Start(self.scanned))?;
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)? as u64;
+            // A line still being written is picked up next time.
+            if read == 0 || !line.ends_with('\n') {
+                break;
+            }
+            if let Ok(stamp) = serde_json::from_str::<Stamp>(&line) {
+                self.runs.push(StoredRun {
+                    offset: self.scanned,
+                    len: read,
+                    time: stamp.time,
+                });
+            }
+            self.scanned += read;
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn time(&self, idx: usize) -> Option<NaiveDateTime> {
+        self.runs.get(idx).map(|run| run.time)
+    }
+
+    /// Reads the runs at `indices` from disk, unless they were just read.
+    pub fn load(&mut self, indices: &[usize]) {
+        for &idx in indices {
+            if self.get(idx).is_some() {
+                continue;
+            }
+            let Some(run) = self.runs.get(idx) else {
+                continue;
+            };
+            match self.read(*run) {
+                Ok(out) => {
+                    if self.cache.len() >= CACHED_RUNS {
+                        self.cache.remove(0);
+                    }
+                    self.cache.push((idx, out));
+                }
+                Err(e) => warn!("Failed to read run {} of {:?}: {}", idx, self.path, e),
+            }
+        }
+    }
+
+    /// A run read by [`StoredHistory::load`].
+    pub fn get(&self, idx: usize) -> Option<&CommandOutput> {
+        self.cache
+            .iter()
+            .find(|(i, _)| *i == idx)
+            .map(|(_, out)| out)
+    }
+
+    fn read(&self, run: StoredRun) -> io::Result<CommandOutput> {
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(run.offset))?;
+        let mut line = String::new();
+        file.take(run.len).read_to_string(&mut line)?;
+        serde_json::from_str(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::history::store::HistoryStore;
+    use crate::history::{store_path, Retention, StoreKey};
+
+    fn mock_output(stdout: &str) -> CommandOutput {
+        CommandOutput {
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            order: Vec::new(),
+            time: chrono::Local::now().naive_local(),
+            exit_status: Some(0),
+            duration: Duration::from_millis(1),
+            timed_out: false,
+            attempts: 1,
+            usage: None,
+            repeats: 1,
+            first_seen: None,
+        }
+    }
+
+    #[test]
+    fn test_browses_stored_runs() {
+        let dir = std::env::temp_dir().join(format!("pane-history-{}", std::process::id()));
+        let retention = Retention {
+            max_runs: Some(3),
+            max_age: None,
+            max_bytes: None,
+        };
+        let path = store_path(&dir, &StoreKey::plain("echo"));
+        let mut store = HistoryStore::new(path, retention);
+
+        for i in 0..5 {
+            store.append(&mock_output(&format!("run {}\n", i))).unwrap();
+        }
+        let mut history = StoredHistory::open(store.path().to_path_buf());
+        assert_eq!(history.len(), 3);
+
+        // The oldest run is dropped as each new one goes past the limit.
+        store.append(&mock_output("run 5\n")).unwrap();
+        history.refresh();
+        assert_eq!(history.len(), 3);
+
+        history.load(&[0, 2]);
+        assert_eq!(history.get(0).unwrap().stdout, "run 3\n");
+        assert_eq!(history.get(2).unwrap().stdout, "run 5\n");
+        assert!(history.get(1).is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,5 @@
+mod browse;
+mod store;
+
+pub use browse::StoredHistory;
+pub use store::{store_path, HistoryWriter, Retention, StoreKey};
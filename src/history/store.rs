@@ -0,0 +1,477 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use chrono::{Local, NaiveDateTime};
+use humantime::format_duration;
+use serde::{Deserialize, Serialize};
+
+use crate::command::{Command, CommandOutput, ExecMode};
+use crate::config::utils::{deserialize_optional_duration, serialize_optional_duration};
+use crate::logging::warn;
+use crate::ui::utils::formatting::format_bytes;
+
+/// Runs past the age limit are dropped after this many runs are added,
+/// besides on the first. The run and size limits are checked on every run.
+const COMPACT_EVERY: usize = 100;
+
+/// How much of a pane's history is kept on disk. Whichever limit is reached
+/// first drops the oldest runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Retention {
+    pub max_runs: Option<usize>,
+    #[serde(
+        deserialize_with = "deserialize_optional_duration",
+        serialize_with = "serialize_optional_duration"
+    )]
+    pub max_age: Option<Duration>,
+    pub max_bytes: Option<u64>,
+}
+
+/// The time of a stored run, read without the rest of it.
+#[derive(Deserialize)]
+pub(super) struct Stamp {
+    #[serde(with = "crate::command::naivedatetime_format")]
+    pub time: NaiveDateTime,
+}
+
+/// A pane's runs, appended one JSON object per line to a file of their own.
+#[derive(Debug)]
+pub struct HistoryStore {
+    path: PathBuf,
+    retention: Retention,
+    appended: usize,
+    /// How many runs and bytes the file holds, once it's been read.
+    size: Option<(usize, u64)>,
+}
+
+/// FNV-1a, which unlike the std hasher is the same across releases, so
+/// a command maps to the same file every time.
+fn stable_hash(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// What a pane's runs are stored by: the same command run another way, in
+/// another directory or with another environment gets a file of its own.
+pub struct StoreKey<'a> {
+    pub exec: &'a str,
+    pub exec_mode: ExecMode,
+    pub shell: Option<&'a str>,
+    pub cwd: Option<&'a Path>,
+    pub env: &'a BTreeMap<String, String>,
+}
+
+impl<'a> From<&'a Command> for StoreKey<'a> {
+    fn from(command: &'a Command) -> Self {
+        StoreKey {
+            exec: &command.exec,
+            exec_mode: command.exec_mode,
+            shell: command.shell.as_deref(),
+            cwd: command.cwd.as_deref(),
+            env: &command.env,
+        }
+    }
+}
+
+#[cfg(test)]
+impl<'a> StoreKey<'a> {
+    /// A command run the default way.
+    pub fn plain(exec: &'a str) -> Self {
+        static NO_ENV: BTreeMap<String, String> = BTreeMap::new();
+        StoreKey {
+            exec,
+            exec_mode: ExecMode::default(),
+            shell: None,
+            cwd: None,
+            env: &NO_ENV,
+        }
+    }
+}
+
+/// The file the runs of the command `key` describes are stored in under
+/// `dir`. Without a `cwd` the command runs in ours, so restarting a pane in
+/// the same directory finds the same file.
+pub fn store_path(dir: &Path, key: &StoreKey) -> PathBuf {
+    let cwd = key
+        .cwd
+        .map(Path::to_path_buf)
+        .or_else(|| env::current_dir().ok());
+    let mut identity = format!(
+        "{}\0{}\0{}\0{}",
+        key.exec,
+        key.exec_mode,
+        key.shell.unwrap_or_default(),
+        cwd.unwrap_or_default().display()
+    );
+    for (name, value) in key.env {
+        identity.push_str(&format!("\0{}={}", name, value));
+    }
+    dir.join(format!("pane-{:016x}.jsonl", stable_hash(&identity)))
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf, retention: Retention) -> Self {
+        Self {
+            path,
+            retention,
+            appended: 0,
+            size: None,
+        }
+    }
+
+    pub fn append(&mut self, out: &CommandOutput) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut line =
+            serde_json::to_string(out).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())?;
+
+        self.size = self
+            .size
+            .map(|(runs, bytes)| (runs + 1, bytes + line.len() as u64));
+
+        if self.appended.is_multiple_of(COMPACT_EVERY) || self.over_limit() {
+            self.compact()?;
+        }
+        self.appended += 1;
+        Ok(())
+    }
+
+    /// Whether the file holds more runs or bytes than it may, as far as is
+    /// known without reading it.
+    fn over_limit(&self) -> bool {
+        self.size.is_some_and(|(runs, bytes)| {
+            self.retention.max_runs.is_some_and(|max| runs > max)
+                || self.retention.max_bytes.is_some_and(|max| bytes > max)
+        })
+    }
+
+    /// Drops the oldest runs past the retention limits, rewriting the file
+    /// with the ones kept.
+    pub fn compact(&mut self) -> io::Result<()> {
+        let content = match fs::read(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                self.size = Some((0, 0));
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let runs: Vec<(u64, Option<NaiveDateTime>)> = content
+            .split_inclusive(|&byte| byte == b'\n')
+            .map(|line| {
+                let time = serde_json::from_slice::<Stamp>(line).ok().map(|s| s.time);
+                (line.len() as u64, time)
+            })
+            .collect();
+
+        let drop = self
+            .retention
+            .runs_to_drop(&runs, Local::now().naive_local());
+        let dropped_bytes: u64 = runs[..drop].iter().map(|(len, _)| len).sum();
+        self.size = Some((runs.len() - drop, content.len() as u64 - dropped_bytes));
+        if drop == 0 {
+            return Ok(());
+        }
+
+        let tmp = self.path.with_extension("jsonl.tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&tmp)?);
+            writer.write_all(&content[dropped_bytes as usize..])?;
+            writer.flush()?;
+        }
+        fs::rename(&tmp, &self.path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// A run to append to the store at `path`.
+struct Append {
+    path: PathBuf,
+    retention: Retention,
+    out: CommandOutput,
+}
+
+/// Appends runs to their stores on a thread of its own, started with the
+/// first run, so the event loop never waits on the disk. Runs still queued
+/// when it's dropped are written first.
+#[derive(Debug, Default)]
+pub struct HistoryWriter {
+    tx: Option<mpsc::Sender<Append>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HistoryWriter {
+    pub fn append(&mut self, path: PathBuf, retention: Retention, out: CommandOutput) {
+        let tx = self.tx.get_or_insert_with(|| {
+            let (tx, rx) = mpsc::channel();
+            self.thread = Some(thread::spawn(move || write_runs(rx)));
+            tx
+        });
+        let append = Append {
+            path,
+            retention,
+            out,
+        };
+        if let Err(e) = tx.send(append) {
+            warn!(
+                "History writer stopped, not storing output in {:?}",
+                e.0.path
+            );
+        }
+    }
+}
+
+impl Drop for HistoryWriter {
+    fn drop(&mut self) {
+        // Closing the channel ends the thread once it's written what's queued.
+        self.tx = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn write_runs(rx: mpsc::Receiver<Append>) {
+    let mut stores: HashMap<PathBuf, HistoryStore> = HashMap::new();
+    for Append {
+        path,
+        retention,
+        out,
+    } in rx
+    {
+        let store = stores
+            .entry(path.clone())
+            .or_insert_with(|| HistoryStore::new(path, retention));
+        if let Err(e) = store.append(&out) {
+            warn!("Failed to store output in {:?}: {}", store.path(), e);
+        }
+    }
+}
+
+impl Retention {
+    /// How many of the oldest `runs`, given as their size in bytes and time,
+    /// are past a limit at `now`. Runs whose time can't be read are dropped.
+    fn runs_to_drop(&self, runs: &[(u64, Option<NaiveDateTime>)], now: NaiveDateTime) -> usize {
+        let mut drop = match self
+            .max_age
+            .and_then(|age| chrono::Duration::from_std(age).ok())
+        {
+            Some(age) => runs
+                .iter()
+                .take_while(|(_, time)| time.is_none_or(|t| now - t > age))
+                .count(),
+            None => 0,
+        };
+
+        if let Some(max_runs) = self.max_runs {
+            drop = drop.max(runs.len().saturating_sub(max_runs));
+        }
+
+        if let Some(max_bytes) = self.max_bytes {
+            let mut bytes: u64 = runs[drop..].iter().map(|(len, _)| len).sum();
+            while bytes > max_bytes && drop < runs.len() {
+                bytes -= runs[drop].0;
+                drop += 1;
+            }
+        }
+        drop
+    }
+}
+
+impl fmt::Display for Retention {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut limits = Vec::new();
+        if let Some(max_runs) = self.max_runs {
+            limits.push(format!("{} runs", max_runs));
+        }
+        if let Some(max_age) = self.max_age {
+            limits.push(format_duration(max_age).to_string());
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            limits.push(format_bytes(max_bytes));
+        }
+
+        if limits.is_empty() {
+            write!(f, "Unlimited")
+        } else {
+            write!(f, "{}", limits.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::StoredHistory;
+
+    fn mock_output(stdout: &str) -> CommandOutput {
+        CommandOutput {
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            order: Vec::new(),
+            time: Local::now().naive_local(),
+            exit_status: Some(0),
+            duration: Duration::from_millis(1),
+            timed_out: false,
+            attempts: 1,
+            usage: None,
+            repeats: 1,
+            first_seen: None,
+        }
+    }
+
+    fn at(secs: i64) -> Option<NaiveDateTime> {
+        chrono::DateTime::from_timestamp(secs, 0).map(|t| t.naive_utc())
+    }
+
+    #[test]
+    fn test_retention_drops_oldest_runs() {
+        let runs: Vec<(u64, Option<NaiveDateTime>)> = (0..10).map(|i| (100, at(i * 60))).collect();
+        let now = at(10 * 60).unwrap();
+        let keep = |retention: Retention| runs.len() - retention.runs_to_drop(&runs, now);
+
+        let unlimited = Retention {
+            max_runs: None,
+            max_age: None,
+            max_bytes: None,
+        };
+        assert_eq!(keep(unlimited), 10);
+        assert_eq!(
+            keep(Retention {
+                max_runs: Some(4),
+                ..unlimited
+            }),
+            4
+        );
+        // Runs at most five minutes old, the one at exactly five included.
+        assert_eq!(
+            keep(Retention {
+                max_age: Some(Duration::from_secs(300)),
+                ..unlimited
+            }),
+            5
+        );
+        assert_eq!(
+            keep(Retention {
+                max_runs: Some(8),
+                max_bytes: Some(350),
+                ..unlimited
+            }),
+            3
+        );
+    }
+
+    #[test]
+    fn test_store_path_is_keyed_on_how_the_command_runs() {
+        let dir = Path::new("/history");
+        let path = |key: StoreKey| store_path(dir, &key);
+        let ls = || StoreKey::plain("ls");
+        let kubeconfig = BTreeMap::from([("KUBECONFIG".to_string(), "b".to_string())]);
+
+        assert_eq!(path(ls()), path(ls()));
+        assert_ne!(path(ls()), path(StoreKey::plain("ls -l")));
+        assert_ne!(
+            path(ls()),
+            path(StoreKey {
+                cwd: Some(Path::new("/var")),
+                ..ls()
+            })
+        );
+        assert_ne!(
+            path(ls()),
+            path(StoreKey {
+                env: &kubeconfig,
+                ..ls()
+            })
+        );
+        assert_ne!(
+            path(ls()),
+            path(StoreKey {
+                shell: Some("bash"),
+                ..ls()
+            })
+        );
+        assert_ne!(
+            path(ls()),
+            path(StoreKey {
+                exec_mode: ExecMode::Direct,
+                ..ls()
+            })
+        );
+        let here = env::current_dir().unwrap();
+        assert_eq!(
+            path(ls()),
+            path(StoreKey {
+                cwd: Some(&here),
+                ..ls()
+            })
+        );
+    }
+
+    #[test]
+    fn test_size_limit_is_kept_on_every_append() {
+        let dir = std::env::temp_dir().join(format!("pane-size-{}", std::process::id()));
+        let path = store_path(&dir, &StoreKey::plain("echo"));
+        let max_bytes = 1000;
+        let mut store = HistoryStore::new(
+            path.clone(),
+            Retention {
+                max_bytes: Some(max_bytes),
+                ..Retention::default()
+            },
+        );
+
+        for i in 0..20 {
+            store.append(&mock_output(&format!("run {}\n", i))).unwrap();
+            assert!(fs::metadata(&path).unwrap().len() <= max_bytes);
+        }
+        let history = StoredHistory::open(path);
+        assert!(history.len() > 1 && history.len() < 20);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_writer_stores_queued_runs_when_dropped() {
+        let dir = std::env::temp_dir().join(format!("pane-writer-{}", std::process::id()));
+        let path = store_path(&dir, &StoreKey::plain("echo"));
+
+        let mut writer = HistoryWriter::default();
+        for i in 0..3 {
+            writer.append(
+                path.clone(),
+                Retention::default(),
+                mock_output(&format!("run {}\n", i)),
+            );
+        }
+        drop(writer);
+
+        let mut history = StoredHistory::open(path);
+        assert_eq!(history.len(), 3);
+        history.load(&[2]);
+        assert_eq!(history.get(2).unwrap().stdout, "run 2\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,4 +1,4 @@
-use std::io::{self, stdout};
+use std::io::{self, stdout, Write};
 
 use crokey::crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
@@ -10,11 +10,13 @@ use ratatui::Terminal;
 use crate::app::App;
 use crate::logging::info;
 
+mod alert;
 mod app;
 mod cli;
 mod command;
 mod config;
 mod controls;
+mod history;
 mod logging;
 mod mode;
 mod pane;
@@ -71,14 +73,22 @@ async fn main() -> color_eyre::Result<()> {
 
     let mut terminal = init()?;
 
-    let app_result = {
-        let mut app = App::new(config, cli_args.command);
-        app.run(&mut terminal).await
-    };
+    let mut app = App::new(config, cli_args.command);
+    let app_result = app.run(&mut terminal).await;
 
     restore()?;
 
     info!("Application shutting down.");
 
+    if let Some(change) = app.change_exit.take() {
+        app_result?;
+        println!("{}", change.pane_id);
+        print!("{}", change);
+        stdout().flush()?;
+        // Exiting skips destructors, so the queued runs are stored first.
+        drop(app);
+        std::process::exit(change.exit_code());
+    }
+
     app_result
 }
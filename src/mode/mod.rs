@@ -1,3 +1,4 @@
+mod prompt;
 mod state;
 
 pub use self::state::{AppMode, DiffMode, ObserveFocus};
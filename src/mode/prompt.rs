@@ -0,0 +1,139 @@
+use std::fmt;
+use std::str::FromStr;
+
+use tui_input::Input;
+
+use crate::alert::AlertRule;
+use crate::app::{App, AppControl};
+use crate::command::{CommandControl, Extractor};
+use crate::config::utils::parse_interval;
+use crate::logging::{info, warn};
+use crate::ui::Pipeline;
+
+type Apply = Box<dyn Fn(&mut App, &str) -> Result<(), String> + Send>;
+
+/// A single-line prompt that parses what was typed into a value and hands it
+/// to a callback once confirmed.
+pub struct Prompt {
+    /// Name of the mode, shown in the status line.
+    pub name: &'static str,
+    /// Title of the popup, describing what can be entered.
+    pub title: &'static str,
+    pub input: Input,
+    /// Why the last value entered was rejected.
+    pub error: Option<String>,
+    apply: Apply,
+}
+
+impl Prompt {
+    pub fn new<T>(
+        name: &'static str,
+        title: &'static str,
+        initial: String,
+        apply: fn(&mut App, T) -> Result<(), String>,
+    ) -> Self
+    where
+        T: FromStr + 'static,
+        T::Err: fmt::Display,
+    {
+        Prompt {
+            name,
+            title,
+            input: Input::default().with_value(initial),
+            error: None,
+            apply: Box::new(move |app, value| {
+                let value = value.parse::<T>().map_err(|e| e.to_string())?;
+                apply(app, value)
+            }),
+        }
+    }
+
+    /// Parses the current input and applies it.
+    pub fn confirm(&self, app: &mut App) -> Result<(), String> {
+        (self.apply)(app, self.input.value())
+    }
+}
+
+impl fmt::Debug for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prompt")
+            .field("name", &self.name)
+            .field("input", &self.input)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A prompt value that can be left empty to clear the setting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Optional<T>(pub Option<T>);
+
+impl<T: FromStr> FromStr for Optional<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" => Ok(Optional(None)),
+            value => value.parse().map(|v| Optional(Some(v))),
+        }
+    }
+}
+
+pub fn set_interval(app: &mut App, value: String) -> Result<(), String> {
+    let interval = parse_interval(&value)?;
+    let id = app.pane_manager.active_pane_id;
+    if let Err(e) = app.app_control_tx.try_send(AppControl::SendControl(
+        id,
+        CommandControl::IntervalSet(interval),
+    )) {
+        warn!("Failed to send AppControl::SendControl: {}", e);
+    }
+    Ok(())
+}
+
+/// Confirming an empty prompt removes the pane's alerts.
+pub fn add_alert(app: &mut App, rule: Optional<AlertRule>) -> Result<(), String> {
+    let id = app.pane_manager.active_pane_id;
+    if let Some(command) = app.tasks.get_mut(&id) {
+        match rule.0 {
+            Some(rule) => {
+                info!("Pane {:?} alert added: {}", id, rule);
+                command.add_alert(rule);
+            }
+            None => {
+                info!("Pane {:?} alerts cleared", id);
+                command.clear_alerts();
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn set_hook(app: &mut App, spec: String) -> Result<(), String> {
+    let id = app.pane_manager.active_pane_id;
+    if let Some(command) = app.tasks.get_mut(&id) {
+        command.hooks.set(&spec)?;
+        info!("Pane {:?} hooks set to {}", id, command.hooks);
+    }
+    Ok(())
+}
+
+/// Confirming an empty prompt goes back to parsing the whole output.
+pub fn set_extractor(app: &mut App, extractor: Optional<Extractor>) -> Result<(), String> {
+    let id = app.pane_manager.active_pane_id;
+    if let Some(command) = app.tasks.get_mut(&id) {
+        info!("Pane {:?} extractor set to {:?}", id, extractor.0);
+        command.extractor = extractor.0;
+    }
+    Ok(())
+}
+
+/// Confirming an empty prompt plots the values as they are.
+pub fn set_transforms(app: &mut App, transforms: Pipeline) -> Result<(), String> {
+    let id = app.pane_manager.active_pane_id;
+    if let Some(command) = app.tasks.get_mut(&id) {
+        info!("Pane {:?} transforms set to '{}'", id, transforms);
+        command.transforms = transforms;
+    }
+    Ok(())
+}
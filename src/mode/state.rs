@@ -1,15 +1,21 @@
 use std::fmt;
+use std::time::Duration;
+
+use humantime::format_duration;
 
 use ratatui::widgets::{ListState, ScrollbarState};
 use strum::IntoEnumIterator;
 use tui_input::Input;
 
 use crate::app::App;
+use crate::command::Extractor;
 use crate::controls::KeyMode;
+use crate::history::{store_path, StoreKey, StoredHistory};
+use crate::mode::prompt::{self, Prompt};
 use crate::pane::PaneKey;
 use crate::session;
 use crate::shell_history::ShellHistoryManager;
-use crate::ui::DisplayType;
+use crate::ui::{DisplayType, Pipeline};
 
 #[derive(Debug, Default)]
 pub enum AppMode {
@@ -28,6 +34,7 @@ pub enum AppMode {
     SessionSave {
         input: Input,
     },
+    Prompt(Prompt),
     DisplayTypeSelect {
         state: ListState,
         items: Vec<DisplayType>,
@@ -48,6 +55,8 @@ pub enum AppMode {
         scroll_offset: u16,
         max_scroll: u16,
         scrollbar_state: ScrollbarState,
+        /// The pane's runs on disk, browsed instead of the ones in memory.
+        stored: Option<StoredHistory>,
     },
 }
 
@@ -86,6 +95,7 @@ impl fmt::Display for AppMode {
             AppMode::CmdEdit { .. } => "Command Edit",
             AppMode::SessionLoad { .. } => "Load Session",
             AppMode::SessionSave { .. } => "Save Session",
+            AppMode::Prompt(prompt) => prompt.name,
             AppMode::DisplayTypeSelect { .. } => "Select Display",
             AppMode::Help { .. } => "Help",
             AppMode::Observe { .. } => "Observe",
@@ -101,6 +111,7 @@ impl AppMode {
             AppMode::CmdEdit { .. } => KeyMode::CmdEdit,
             AppMode::SessionLoad { .. } => KeyMode::SessionLoad,
             AppMode::SessionSave { .. } => KeyMode::SessionSave,
+            AppMode::Prompt(_) => KeyMode::Prompt,
             AppMode::DisplayTypeSelect { .. } => KeyMode::DisplayTypeSelect,
             AppMode::Observe { .. } => KeyMode::Observe,
             AppMode::Help { .. } => KeyMode::Help,
@@ -136,6 +147,51 @@ impl AppMode {
         }
     }
 
+    pub fn new_interval_edit(interval: Duration) -> Self {
+        AppMode::Prompt(Prompt::new(
+            "Set Interval",
+            "Set Interval (e.g. 0.5, 250ms, 1m30s)",
+            format_duration(interval).to_string(),
+            prompt::set_interval,
+        ))
+    }
+
+    pub fn new_alert_edit() -> Self {
+        AppMode::Prompt(Prompt::new(
+            "Add Alert",
+            "Add Alert (e.g. value > 90 => beep, notify; !match /ok/ => banner; empty clears)",
+            String::new(),
+            prompt::add_alert,
+        ))
+    }
+
+    pub fn new_hook_edit() -> Self {
+        AppMode::Prompt(Prompt::new(
+            "Set Hook",
+            "Set Hook (change|fail|recover = COMMAND, e.g. fail = notify-send down; no command clears)",
+            String::new(),
+            prompt::set_hook,
+        ))
+    }
+
+    pub fn new_extractor_edit(extractor: Option<&Extractor>) -> Self {
+        AppMode::Prompt(Prompt::new(
+            "Extract Value",
+            "Extract Value (e.g. /load: ([\\d.]+)/, $.data.count, 2:3 for line 2 column 3)",
+            extractor.map(|e| e.to_string()).unwrap_or_default(),
+            prompt::set_extractor,
+        ))
+    }
+
+    pub fn new_transform_edit(transforms: &Pipeline) -> Self {
+        AppMode::Prompt(Prompt::new(
+            "Transform Values",
+            "Transform Values (e.g. rate | avg 5, delta, envelope 10, clamp 0 100)",
+            transforms.to_string(),
+            prompt::set_transforms,
+        ))
+    }
+
     pub fn new_display_type_select() -> Self {
         let items: Vec<DisplayType> = DisplayType::iter().collect();
 
@@ -169,6 +225,16 @@ impl AppMode {
     pub fn new_observing(app: &App) -> Self {
         let diff_mode = DiffMode::default();
         let active_id = app.pane_manager.active_pane_id;
+        let stored = app
+            .tasks
+            .get(&active_id)
+            .filter(|_| app.config.store_history)
+            .map(|command| {
+                StoredHistory::open(store_path(
+                    &app.config.history_dir,
+                    &StoreKey::from(command),
+                ))
+            });
 
         AppMode::Observe {
             active_id: active_id,
@@ -181,6 +247,7 @@ impl AppMode {
             scroll_offset: 0,
             max_scroll: 0,
             scrollbar_state: ScrollbarState::default(),
+            stored,
         }
     }
 }
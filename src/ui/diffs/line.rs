@@ -1,16 +1,22 @@
 use ratatui::text::{Line, Span};
 use similar::{ChangeTag, TextDiff};
 
-use crate::{config::theme::Theme, ui::utils::highlight_query};
+use super::unchanged_spans;
+use crate::{
+    config::theme::Theme,
+    ui::utils::{highlight_query, AnsiText},
+};
 
 pub fn render<'a>(
     theme: &Theme,
-    current: &'a str,
-    previous: &'a str,
+    current: &'a AnsiText,
+    previous: &'a AnsiText,
     query: &str,
 ) -> Vec<Line<'a>> {
-    let diff = TextDiff::from_lines(previous, current);
+    let diff = TextDiff::from_lines(&previous.plain, &current.plain);
     let mut lines = Vec::new();
+    // Position in the current output, to look up its colors for unchanged lines.
+    let mut offset = 0;
 
     let p = &theme.palette;
 
@@ -21,8 +27,15 @@ pub fn render<'a>(
             ChangeTag::Equal => (" ", p.output),
         };
 
+        let value = change.value();
         let mut spans = vec![Span::styled(sign, style)];
-        let highlighted_parts = highlight_query(change.value(), &query, style, p.search_match);
+        let highlighted_parts = match change.tag() {
+            ChangeTag::Equal => unchanged_spans(p, current, value, offset, style, query),
+            _ => highlight_query(value, &query, style, p.search_match),
+        };
+        if change.tag() != ChangeTag::Delete {
+            offset += value.len();
+        }
 
         spans.extend(highlighted_parts);
         lines.push(Line::from(spans));
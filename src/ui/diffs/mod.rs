@@ -1,6 +1,9 @@
-use ratatui::text::Line;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
 
-use crate::{config::theme::Theme, mode::DiffMode};
+use crate::config::theme::{Palette, Theme};
+use crate::mode::DiffMode;
+use crate::ui::utils::{highlight_query, AnsiText};
 
 pub mod char;
 pub mod line;
@@ -9,8 +12,8 @@ pub mod word;
 
 pub fn render_diff<'a>(
     theme: &Theme,
-    current: &'a str,
-    previous: &'a str,
+    current: &'a AnsiText,
+    previous: &'a AnsiText,
     mode: DiffMode,
     query: &str,
 ) -> Vec<Line<'a>> {
@@ -21,3 +24,22 @@ pub fn render_diff<'a>(
         DiffMode::Char => char::render(theme, current, previous, query),
     }
 }
+
+/// Spans for an unchanged `part` found at `offset` in the current output,
+/// keeping the output's own colors on top of `style`.
+fn unchanged_spans<'a>(
+    p: &Palette,
+    current: &AnsiText,
+    part: &'a str,
+    offset: usize,
+    style: Style,
+    query: &str,
+) -> Vec<Span<'a>> {
+    current
+        .styled_pieces(part, offset)
+        .into_iter()
+        .flat_map(|(piece, colors)| {
+            highlight_query(piece, query, style.patch(colors), p.search_match)
+        })
+        .collect()
+}
@@ -1,14 +1,19 @@
 use ratatui::text::Line;
 
-use crate::{config::theme::Theme, ui::utils::highlight_query};
+use super::unchanged_spans;
+use crate::{config::theme::Theme, ui::utils::AnsiText};
 
-pub fn render<'a>(theme: &Theme, current: &'a str, query: &str) -> Vec<Line<'a>> {
+pub fn render<'a>(theme: &Theme, current: &'a AnsiText, query: &str) -> Vec<Line<'a>> {
     let p = &theme.palette;
+    let mut offset = 0;
 
     current
-        .lines()
-        .map(|line_content| {
-            let spans = highlight_query(line_content, &query, p.output, p.search_match);
+        .plain
+        .split_inclusive('\n')
+        .map(|raw| {
+            let line_content = raw.trim_end_matches(['\n', '\r']);
+            let spans = unchanged_spans(p, current, line_content, offset, p.output, query);
+            offset += raw.len();
             Line::from(spans)
         })
         .collect()
@@ -1,17 +1,23 @@
 use ratatui::text::Line;
 use similar::{ChangeTag, TextDiff};
 
-use crate::{config::theme::Theme, ui::utils::highlight_query};
+use super::unchanged_spans;
+use crate::{
+    config::theme::Theme,
+    ui::utils::{highlight_query, AnsiText},
+};
 
 pub fn render<'a>(
     theme: &Theme,
-    current: &'a str,
-    previous: &'a str,
+    current: &'a AnsiText,
+    previous: &'a AnsiText,
     query: &str,
 ) -> Vec<Line<'a>> {
-    let diff = TextDiff::from_words(previous, current);
+    let diff = TextDiff::from_words(&previous.plain, &current.plain);
     let mut lines = Vec::new();
     let mut current_line_spans = Vec::new();
+    // Position in the current output, to look up its colors for unchanged text.
+    let mut offset = 0;
 
     let p = &theme.palette;
 
@@ -28,24 +34,30 @@ pub fn render<'a>(
         };
 
         let value = change.value();
+        let is_equal = change.tag() == ChangeTag::Equal;
 
-        if value.contains('\n') {
-            let parts: Vec<&str> = value.split('\n').collect();
+        let parts: Vec<&str> = value.split('\n').collect();
+        let mut part_offset = offset;
 
-            for (i, part) in parts.iter().enumerate() {
-                if !part.is_empty() {
-                    let highlighted_parts = highlight_query(part, &query, style, p.search_match);
-                    current_line_spans.extend(highlighted_parts);
-                }
+        for (i, part) in parts.iter().enumerate() {
+            if !part.is_empty() {
+                let highlighted_parts = if is_equal {
+                    unchanged_spans(p, current, part, part_offset, style, query)
+                } else {
+                    highlight_query(part, &query, style, p.search_match)
+                };
+                current_line_spans.extend(highlighted_parts);
+            }
+            part_offset += part.len() + 1;
 
-                if i < parts.len() - 1 {
-                    lines.push(Line::from(current_line_spans));
-                    current_line_spans = Vec::new();
-                }
+            if i < parts.len() - 1 {
+                lines.push(Line::from(current_line_spans));
+                current_line_spans = Vec::new();
             }
-        } else {
-            let highlighted_parts = highlight_query(value, &query, style, p.search_match);
-            current_line_spans.extend(highlighted_parts);
+        }
+
+        if change.tag() != ChangeTag::Delete {
+            offset += value.len();
         }
     }
 
@@ -1,109 +1,280 @@
+use std::ops::Range;
+
+use chrono::{DateTime, Local, NaiveDateTime};
 use ratatui::{
-    layout::{Alignment, Rect},
+    layout::{Alignment, Constraint, Layout, Rect},
     style::Style,
     symbols,
-    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph},
+    text::Line,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, LegendPosition, Paragraph},
     Frame,
 };
 
+use super::series::{datetime_to_f64, plotted_series, NamedSeries};
+use crate::config::theme::TimeLabels;
 use crate::config::AppConfig;
 use crate::logging::trace;
+use crate::ui::utils::formatting::format_seconds;
 use crate::{command::Command, ui::DisplayType};
 
-use chrono::NaiveDateTime;
+/// Which stretch of the history a chart shows, moved by panning and zooming.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ChartView {
+    /// Entries left out at the newest end, 0 to follow new output.
+    pub offset: usize,
+    /// Entries shown, `None` for as many as fit across the chart.
+    pub span: Option<usize>,
+}
+
+impl ChartView {
+    /// The entries shown out of `len`, when `fit` of them fit across the chart.
+    pub fn window(&self, len: usize, fit: usize) -> Range<usize> {
+        let span = self.span.unwrap_or(fit).clamp(1, len.max(1));
+        let end = len.saturating_sub(self.offset).max(span.min(len));
+        end.saturating_sub(span)..end
+    }
+
+    /// Moves a quarter of the window towards older entries, or newer ones.
+    pub fn pan(&mut self, older: bool, len: usize, fit: usize) {
+        let window = self.window(len, fit);
+        let step = (window.len() / 4).max(1);
+        let offset = len - window.end;
+        self.offset = if older {
+            (offset + step).min(len - window.len())
+        } else {
+            offset.saturating_sub(step)
+        };
+    }
+
+    /// Halves the entries shown, or doubles them up to the whole history.
+    pub fn zoom(&mut self, zoom_in: bool, len: usize, fit: usize) {
+        let span = self.window(len, fit).len();
+        self.span = Some(if zoom_in {
+            (span / 2).max(2)
+        } else {
+            span.saturating_mul(2).min(len)
+        });
+    }
+}
+
+fn f64_to_datetime(x: f64) -> NaiveDateTime {
+    DateTime::from_timestamp_millis((x * 1000.0) as i64)
+        .unwrap_or_default()
+        .naive_utc()
+}
 
-fn datetime_to_f64(dt: NaiveDateTime) -> f64 {
-    let seconds = dt.and_utc().timestamp() as f64;
-    let millis = dt.and_utc().timestamp_subsec_millis() as f64 / 1000.0;
-    seconds + millis
+/// Labels the first, middle and last time on the x-axis.
+fn time_labels(x_min: f64, x_max: f64, style: TimeLabels) -> Vec<String> {
+    let now = Local::now().naive_local();
+    [x_min, (x_min + x_max) / 2.0, x_max]
+        .into_iter()
+        .map(|x| {
+            let time = f64_to_datetime(x);
+            match style {
+                TimeLabels::Clock => time.format("%H:%M:%S").to_string(),
+                TimeLabels::Relative => match (now - time).num_seconds() {
+                    ..=0 => "now".to_string(),
+                    secs => format!("-{}", format_seconds(secs as f64)),
+                },
+            }
+        })
+        .collect()
+}
+
+/// The smallest and largest y of `points`, and how far to pad the axis past them.
+fn y_range<'a>(points: impl Iterator<Item = &'a (f64, f64)>) -> (f64, f64, f64) {
+    let (y_min, y_max) = points.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (_, y)| {
+        (lo.min(*y), hi.max(*y))
+    });
+    let y_padding = ((y_max - y_min) * 0.1).max(y_min * 0.1);
+    (y_min, y_max, y_padding)
 }
 
 pub fn render(frame: &mut Frame, area: Rect, config: &AppConfig, command: &Command) {
     let p = &config.theme.palette;
 
-    let data_result: Result<Vec<(f64, f64)>, &str> = command
-        .output_history
-        .iter()
-        .map(|entry| {
-            let x = datetime_to_f64(entry.time);
-
-            let y = entry
-                .output
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| "Conversion failed")?;
+    if command.output_history.is_empty() {
+        let msg = Paragraph::new("No data.")
+            .style(p.error)
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::empty()));
+        frame.render_widget(msg, area);
+        return;
+    }
 
-            Ok((x, y))
+    // Transforms see the whole history, so the first runs shown still have
+    // the ones before them to compare with.
+    let window = command
+        .chart_view
+        .window(command.output_history.len(), area.width as usize);
+    let shown = datetime_to_f64(command.output_history[window.start].time)
+        ..=datetime_to_f64(command.output_history[window.end - 1].time);
+    let mut series: Vec<NamedSeries> = plotted_series(command)
+        .into_iter()
+        .map(|(name, points)| {
+            let points = points.into_iter().filter(|(x, _)| shown.contains(x));
+            (name, points.collect())
         })
+        .filter(|(_, points): &NamedSeries| !points.is_empty())
         .collect();
 
-    match data_result {
-        Err(_) => {
-            let msg = Paragraph::new("No numeric data found in history to graph.")
-                .style(p.error)
-                .alignment(Alignment::Center)
-                .block(Block::default().borders(Borders::empty()));
-            frame.render_widget(msg, area);
-        }
-        Ok(chart_data) if chart_data.is_empty() => {
-            let msg = Paragraph::new("No data.")
-                .style(p.error)
-                .alignment(Alignment::Center)
-                .block(Block::default().borders(Borders::empty()));
-            frame.render_widget(msg, area);
-        }
-        Ok(chart_data) => {
-            let max_data_points = area.width as usize;
+    if series.is_empty() {
+        let msg = Paragraph::new("No numeric data found in history to graph.")
+            .style(p.error)
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::empty()));
+        frame.render_widget(msg, area);
+        return;
+    }
 
-            let display_data = if chart_data.len() > max_data_points {
-                &chart_data[chart_data.len() - max_data_points..]
-            } else {
-                &chart_data[..]
-            };
+    let (graph_type, marker, style) = match command.display_type {
+        DisplayType::LineChart => (GraphType::Line, symbols::Marker::Braille, p.chart_line),
+        DisplayType::BarChart => (GraphType::Bar, symbols::Marker::Quadrant, p.chart_bar),
+        _ => (GraphType::Scatter, symbols::Marker::Dot, p.chart_scatter),
+    };
+
+    // A lone series keeps the display type's own style.
+    let multiple = series.len() > 1;
+    let series_style = |i: usize| match p.chart_series.len() {
+        n if multiple && n > 0 => p.chart_series[i % n],
+        _ => style,
+    };
+
+    let unit = command.series.unit();
+    let precision = config.theme.chart_precision;
+    let secondary = command.secondary_axis && series.len() > 1;
+    let primary_count = if secondary { 1 } else { series.len() };
 
-            let (graph_type, marker, style) = match command.display_type {
-                DisplayType::LineChart => (GraphType::Line, symbols::Marker::Braille, p.chart_line),
-                DisplayType::BarChart => (GraphType::Bar, symbols::Marker::Quadrant, p.chart_bar),
-                _ => (GraphType::Scatter, symbols::Marker::Dot, p.chart_scatter),
-            };
+    let all_points = || series.iter().flat_map(|(_, points)| points);
+    let x_min = all_points().map(|(x, _)| *x).fold(f64::INFINITY, f64::min);
+    let x_max = all_points()
+        .map(|(x, _)| *x)
+        .fold(f64::NEG_INFINITY, f64::max);
 
-            let datasets = vec![Dataset::default()
+    let (y_min, y_max, y_padding) = y_range(
+        series[..primary_count]
+            .iter()
+            .flat_map(|(_, points)| points),
+    );
+    let (y_low, y_high) = (y_min - y_padding, y_max + y_padding);
+
+    trace!("series: {:?}", series);
+    trace!("x min: {x_min}, x_max: {x_max}");
+    trace!("y min: {y_min}, y_max: {y_max}, y padding: {y_padding}");
+
+    let mut chart_area = area;
+    if secondary {
+        // Series on the second axis are scaled onto the first one's bounds.
+        let (s_min, s_max, s_padding) = y_range(series[1..].iter().flat_map(|(_, points)| points));
+        let (s_low, s_high) = (s_min - s_padding, s_max + s_padding);
+        for (_, points) in &mut series[1..] {
+            for (_, y) in points.iter_mut() {
+                *y = if s_high > s_low {
+                    y_low + (*y - s_low) / (s_high - s_low) * (y_high - y_low)
+                } else {
+                    (y_low + y_high) / 2.0
+                };
+            }
+        }
+
+        let labels = [
+            unit.format(s_high, precision),
+            unit.format(s_low, precision),
+        ];
+        let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0) as u16;
+        let [left, right] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(label_width + 1)])
+                .areas(area);
+        chart_area = left;
+        render_secondary_labels(frame, right, labels, series_style(1));
+    }
+
+    let datasets: Vec<Dataset> = series
+        .iter()
+        .enumerate()
+        .map(|(i, (name, points))| {
+            let dataset = Dataset::default()
                 .marker(marker)
-                .style(style)
+                .style(series_style(i))
                 .graph_type(graph_type)
-                .data(display_data)];
-
-            let x_min = display_data.first().map(|(x, _)| *x).unwrap_or(0.0);
-            let x_max = display_data.last().map(|(x, _)| *x).unwrap_or(0.0);
-
-            let y_min = display_data
-                .iter()
-                .map(|(_, y)| *y)
-                .fold(f64::INFINITY, f64::min);
-            let y_max = display_data
-                .iter()
-                .map(|(_, y)| *y)
-                .fold(f64::NEG_INFINITY, f64::max);
-            let y_padding = ((y_max - y_min) * 0.1).max(y_min * 0.1);
-
-            trace!("data: {:?}", display_data);
-            trace!("x min: {x_min}, x_max: {x_max}");
-            trace!("y min: {y_min}, y_max: {y_max}, y padding: {y_padding}");
-
-            let chart = Chart::new(datasets)
-                .x_axis(
-                    Axis::default()
-                        .style(Style::default().gray())
-                        .bounds([x_min, x_max]),
-                )
-                .y_axis(
-                    Axis::default()
-                        .style(Style::default().gray())
-                        .bounds([y_min - y_padding, y_max + y_padding])
-                        .labels([y_min.to_string(), y_max.to_string()]),
-                );
-
-            frame.render_widget(chart, area);
+                .data(points);
+            // Named datasets get a legend, which a lone series doesn't need.
+            if multiple {
+                dataset.name(name.as_str())
+            } else {
+                dataset
+            }
+        })
+        .collect();
+
+    let chart = Chart::new(datasets)
+        .legend_position(Some(LegendPosition::TopLeft))
+        .x_axis(
+            Axis::default()
+                .style(Style::default().gray())
+                .bounds([x_min, x_max])
+                .labels(time_labels(x_min, x_max, config.theme.chart_time_labels)),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().gray())
+                .bounds([y_low, y_high])
+                // Labelled at the bounds, where ratatui draws the labels.
+                .labels([
+                    unit.format(y_low, precision),
+                    unit.format((y_low + y_high) / 2.0, precision),
+                    unit.format(y_high, precision),
+                ]),
+        );
+
+    frame.render_widget(chart, chart_area);
+}
+
+/// Labels the top and bottom of the second y-axis, level with the first's.
+fn render_secondary_labels(
+    frame: &mut Frame,
+    area: Rect,
+    [top, bottom]: [String; 2],
+    style: Style,
+) {
+    // The plot ends above the x-axis and its labels.
+    let Some(bottom_row) = (area.height as usize).checked_sub(3) else {
+        return;
+    };
+
+    let mut lines = vec![Line::default(); bottom_row + 1];
+    lines[bottom_row] = Line::styled(format!(" {}", bottom), style);
+    lines[0] = Line::styled(format!(" {}", top), style);
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pans_and_zooms_through_history() {
+        let (len, fit) = (100, 40);
+        let mut view = ChartView::default();
+        assert_eq!(view.window(len, fit), 60..100);
+
+        view.pan(true, len, fit);
+        assert_eq!(view.window(len, fit), 50..90);
+        for _ in 0..10 {
+            view.pan(true, len, fit);
         }
+        assert_eq!(view.window(len, fit), 0..40);
+
+        // Zooming keeps the newest end of the window in place.
+        view.zoom(true, len, fit);
+        assert_eq!(view.window(len, fit), 20..40);
+        view.zoom(false, len, fit);
+        view.zoom(false, len, fit);
+        assert_eq!(view.window(len, fit), 0..80);
+
+        view.pan(false, len, fit);
+        assert_eq!(view.window(len, fit), 20..100);
+        assert_eq!(ChartView::default().window(10, fit), 0..10);
     }
 }
@@ -16,7 +16,7 @@ pub fn render(frame: &mut Frame, area: Rect, config: &AppConfig, command: &Comma
 
     let mut counts = HashMap::new();
     for entry in &command.output_history {
-        *counts.entry(&entry.output).or_insert(0) += 1;
+        *counts.entry(entry.text(command.output_view)).or_insert(0) += entry.repeats;
     }
 
     let mut sorted_counts: Vec<_> = counts.into_iter().collect();
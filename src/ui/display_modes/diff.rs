@@ -6,20 +6,27 @@ use ratatui::Frame;
 use crate::command::Command;
 use crate::config::AppConfig;
 use crate::ui::diffs;
+use crate::ui::utils::AnsiText;
 use crate::ui::DisplayType::{DiffChar, DiffLine, DiffWord};
 
 pub fn render(frame: &mut Frame, area: Rect, config: &AppConfig, command: &Command) {
     let current_output = command.output_history.back();
     let previous_output = command.output_history.iter().rev().nth(1);
 
-    let current_str = current_output.map(|c| c.output.as_str()).unwrap_or("");
-    let previous_str = previous_output.map(|c| c.output.as_str()).unwrap_or("");
+    let current = current_output
+        .map(|c| c.text(command.output_view))
+        .unwrap_or_default();
+    let previous = previous_output
+        .map(|c| c.text(command.output_view))
+        .unwrap_or_default();
+    let current = AnsiText::new(&current, !command.strip_ansi);
+    let previous = AnsiText::new(&previous, !command.strip_ansi);
     let query = "";
 
     let lines = match command.display_type {
-        DiffChar => diffs::char::render(&config.theme, current_str, previous_str, query),
-        DiffWord => diffs::word::render(&config.theme, current_str, previous_str, query),
-        DiffLine => diffs::line::render(&config.theme, current_str, previous_str, query),
+        DiffChar => diffs::char::render(&config.theme, &current, &previous, query),
+        DiffWord => diffs::word::render(&config.theme, &current, &previous, query),
+        DiffLine => diffs::line::render(&config.theme, &current, &previous, query),
         _ => Vec::new(),
     };
 
@@ -10,10 +10,16 @@ mod counter;
 pub mod diff;
 mod multiline;
 mod raw_text;
+mod series;
 mod sparkline;
+mod transform;
 pub mod types;
 mod utils;
 
+pub use chart::ChartView;
+pub use series::Series;
+pub use sparkline::sparkline_data;
+pub use transform::Pipeline;
 pub use types::DisplayType;
 
 pub fn render_command_output(
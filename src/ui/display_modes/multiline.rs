@@ -5,6 +5,7 @@ use ratatui::Frame;
 
 use crate::command::Command;
 use crate::config::AppConfig;
+use crate::ui::utils::AnsiText;
 use crate::ui::DisplayType;
 
 pub fn render(frame: &mut Frame, area: Rect, config: &AppConfig, command: &Command) {
@@ -24,10 +25,16 @@ pub fn render(frame: &mut Frame, area: Rect, config: &AppConfig, command: &Comma
                 _ => String::new(),
             };
 
-            Line::from(vec![
+            let text = AnsiText::new(&entry.text(command.output_view), !command.strip_ansi);
+            let mut spans = vec![
                 Span::styled(dt_string, p.multiline_timestamp),
-                Span::styled(format!(" {}", entry.output), p.output),
-            ])
+                Span::styled(" ", p.output),
+            ];
+            for line in text.to_lines(p.output) {
+                spans.extend(line.spans);
+            }
+
+            Line::from(spans)
         })
         .collect();
 
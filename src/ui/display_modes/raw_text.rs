@@ -9,7 +9,7 @@ use crate::ui::display_modes::utils;
 pub fn render(frame: &mut Frame, area: Rect, config: &AppConfig, cmd: &Command) {
     let p = &config.theme.palette;
 
-    let last_output = utils::formatted_last_output(cmd);
+    let last_output = utils::formatted_last_output(cmd, p);
     let mut widget = Paragraph::new(last_output).style(p.output);
 
     if config.wrap {
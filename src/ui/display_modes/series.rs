@@ -0,0 +1,154 @@
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::command::{Command, CommandOutput, Extractor, OutputView};
+use crate::ui::utils::formatting::{format_bytes, format_seconds, format_si};
+
+/// A named series of `(time, value)` points, time in seconds.
+pub type NamedSeries = (String, Vec<(f64, f64)>);
+
+pub fn datetime_to_f64(dt: NaiveDateTime) -> f64 {
+    let seconds = dt.and_utc().timestamp() as f64;
+    let millis = dt.and_utc().timestamp_subsec_millis() as f64 / 1000.0;
+    seconds + millis
+}
+
+/// Every series the pane's whole history plots, in the order each first
+/// shows up, run through the pane's transforms.
+pub fn plotted_series(command: &Command) -> Vec<NamedSeries> {
+    let mut series: Vec<NamedSeries> = Vec::new();
+    for entry in &command.output_history {
+        let x = datetime_to_f64(entry.time);
+        for (name, y) in command.series_values(entry) {
+            match series.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, points)) => points.push((x, y)),
+                None => series.push((name, vec![(x, y)])),
+            }
+        }
+    }
+
+    command.transforms.apply(series)
+}
+
+/// What a chart or sparkline plots for each history entry.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Series {
+    /// The output itself, read as a number.
+    #[default]
+    Value,
+    /// How long the run took.
+    Duration,
+    ExitCode,
+    /// Size of the output, in bytes.
+    OutputSize,
+    UserCpu,
+    SystemCpu,
+    MaxRss,
+    StdoutBytes,
+    StderrBytes,
+}
+
+/// The unit a series is measured in, for labelling its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    None,
+    Seconds,
+    Bytes,
+}
+
+impl Series {
+    pub fn next(self) -> Self {
+        match self {
+            Series::Value => Series::Duration,
+            Series::Duration => Series::ExitCode,
+            Series::ExitCode => Series::OutputSize,
+            Series::OutputSize => Series::UserCpu,
+            Series::UserCpu => Series::SystemCpu,
+            Series::SystemCpu => Series::MaxRss,
+            Series::MaxRss => Series::StdoutBytes,
+            Series::StdoutBytes => Series::StderrBytes,
+            Series::StderrBytes => Series::Value,
+        }
+    }
+
+    /// The entry's value, `None` when it has none to plot. The output's own
+    /// value is read with `extractor` when there is one.
+    pub fn value(
+        self,
+        out: &CommandOutput,
+        view: OutputView,
+        extractor: Option<&Extractor>,
+    ) -> Option<f64> {
+        let usage = out.usage;
+        match self {
+            Series::Value => match extractor {
+                Some(extractor) => extractor.extract(&out.text(view)),
+                None => out.text(view).trim().parse::<f64>().ok(),
+            },
+            Series::Duration => Some(out.duration.as_secs_f64()),
+            Series::ExitCode => out.exit_status.map(f64::from),
+            Series::OutputSize => Some(out.text(view).len() as f64),
+            Series::UserCpu => usage.map(|u| u.user_time.as_secs_f64()),
+            Series::SystemCpu => usage.map(|u| u.system_time.as_secs_f64()),
+            Series::MaxRss => usage.map(|u| u.max_rss as f64),
+            Series::StdoutBytes => usage.map(|u| u.stdout_bytes as f64),
+            Series::StderrBytes => usage.map(|u| u.stderr_bytes as f64),
+        }
+    }
+
+    pub fn unit(self) -> Unit {
+        match self {
+            Series::Value | Series::ExitCode => Unit::None,
+            Series::Duration | Series::UserCpu | Series::SystemCpu => Unit::Seconds,
+            Series::OutputSize | Series::MaxRss | Series::StdoutBytes | Series::StderrBytes => {
+                Unit::Bytes
+            }
+        }
+    }
+}
+
+impl Unit {
+    /// Formats a value of this unit for an axis label, plain numbers with at
+    /// most `precision` decimals.
+    pub fn format(self, value: f64, precision: usize) -> String {
+        match self {
+            Unit::None => format_si(value, precision),
+            Unit::Seconds => format_seconds(value),
+            Unit::Bytes => format_bytes(value.max(0.0) as u64),
+        }
+    }
+}
+
+impl fmt::Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Series::Value => write!(f, "value"),
+            Series::Duration => write!(f, "duration"),
+            Series::ExitCode => write!(f, "exit code"),
+            Series::OutputSize => write!(f, "output size"),
+            Series::UserCpu => write!(f, "user cpu"),
+            Series::SystemCpu => write!(f, "system cpu"),
+            Series::MaxRss => write!(f, "max rss"),
+            Series::StdoutBytes => write!(f, "stdout bytes"),
+            Series::StderrBytes => write!(f, "stderr bytes"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_values_with_unit() {
+        assert_eq!(Series::Duration.unit().format(0.25, 2), "250ms");
+        assert_eq!(Series::Duration.unit().format(90.0, 2), "1m30s");
+        assert_eq!(Series::OutputSize.unit().format(1536.0, 2), "1.5 KiB");
+        assert_eq!(Series::ExitCode.unit().format(1.0, 2), "1");
+        assert_eq!(Series::Value.unit().format(0.123456, 2), "0.12");
+        assert_eq!(Series::Value.unit().format(1250.0, 2), "1.25k");
+        assert_eq!(Series::Value.unit().format(-3_500_000.0, 1), "-3.5M");
+    }
+}
@@ -4,19 +4,35 @@ use ratatui::{
     Frame,
 };
 
+use super::series::plotted_series;
 use crate::command::Command;
 use crate::config::AppConfig;
 
+/// Bars are drawn relative to the largest value, so fractional values like
+/// CPU seconds are scaled up before being truncated to whole numbers.
+pub fn sparkline_data(values: impl Iterator<Item = f64>) -> Vec<u64> {
+    let values: Vec<f64> = values.collect();
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let scale = if max > 0.0 && max < 10_000.0 {
+        10_000.0 / max
+    } else {
+        1.0
+    };
+
+    values.into_iter().map(|v| (v * scale) as u64).collect()
+}
+
 pub fn render(frame: &mut Frame, area: Rect, config: &AppConfig, command: &Command) {
     let p = &config.theme.palette;
 
-    let numeric_data: Vec<Option<u64>> = command
-        .output_history
-        .iter()
-        .map(|entry| entry.output.trim().parse::<f64>().ok().map(|v| v as u64))
-        .collect();
+    // Only the first series fits in a sparkline.
+    let values: Vec<f64> = plotted_series(command)
+        .into_iter()
+        .next()
+        .map(|(_, points)| points.into_iter().map(|(_, y)| y).collect())
+        .unwrap_or_default();
 
-    if numeric_data.iter().all(|val| val.is_none()) {
+    if values.is_empty() {
         let msg = Paragraph::new("No numeric data found in history to graph.")
             .style(p.error)
             .alignment(ratatui::layout::Alignment::Center)
@@ -25,7 +41,7 @@ pub fn render(frame: &mut Frame, area: Rect, config: &AppConfig, command: &Comma
         return;
     }
 
-    let data: Vec<u64> = numeric_data.into_iter().flatten().collect();
+    let data = sparkline_data(values.into_iter());
 
     let max_data_points = area.width as usize;
 
@@ -0,0 +1,242 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::series::NamedSeries;
+
+/// One step of a [`Pipeline`], turning a series into another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    /// Change since the previous value.
+    Delta,
+    /// Change per second since the previous value, for increasing counters.
+    Rate,
+    /// Mean of the last `n` values.
+    MovingAverage(usize),
+    /// Adds the smallest and largest of the last `n` values as series of
+    /// their own, after the series itself.
+    Envelope(usize),
+    /// Keeps values within `min` and `max`.
+    Clamp(f64, f64),
+}
+
+/// Transforms applied in order to every series before it's plotted, written
+/// like `rate | avg 5 | clamp 0 100`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pipeline(pub Vec<Transform>);
+
+impl Transform {
+    fn apply(self, (name, points): NamedSeries) -> Vec<NamedSeries> {
+        match self {
+            Transform::Delta => vec![(name, differences(&points, |dy, _| Some(dy)))],
+            Transform::Rate => vec![(
+                name,
+                differences(&points, |dy, dx| (dx > 0.0).then(|| dy / dx)),
+            )],
+            Transform::MovingAverage(n) => {
+                let averaged = rolling(&points, n, |window| {
+                    window.iter().sum::<f64>() / window.len() as f64
+                });
+                vec![(name, averaged)]
+            }
+            Transform::Envelope(n) => {
+                let min = rolling(&points, n, |w| {
+                    w.iter().copied().fold(f64::INFINITY, f64::min)
+                });
+                let max = rolling(&points, n, |w| {
+                    w.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+                });
+                let (min_name, max_name) = (format!("{} min", name), format!("{} max", name));
+                vec![(name, points), (min_name, min), (max_name, max)]
+            }
+            Transform::Clamp(min, max) => {
+                let clamped = points
+                    .iter()
+                    .map(|(x, y)| (*x, y.clamp(min, max)))
+                    .collect();
+                vec![(name, clamped)]
+            }
+        }
+    }
+}
+
+/// Maps each pair of neighbouring points to `f(dy, dx)`, dropping the first
+/// point, which has nothing before it.
+fn differences(points: &[(f64, f64)], f: impl Fn(f64, f64) -> Option<f64>) -> Vec<(f64, f64)> {
+    points
+        .windows(2)
+        .filter_map(|pair| {
+            let [(x0, y0), (x1, y1)] = pair else {
+                return None;
+            };
+            f(y1 - y0, x1 - x0).map(|y| (*x1, y))
+        })
+        .collect()
+}
+
+/// Maps each point to `f` of the values of up to the last `n` points.
+fn rolling(points: &[(f64, f64)], n: usize, f: impl Fn(&[f64]) -> f64) -> Vec<(f64, f64)> {
+    let values: Vec<f64> = points.iter().map(|(_, y)| *y).collect();
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, (x, _))| (*x, f(&values[(i + 1).saturating_sub(n)..=i])))
+        .collect()
+}
+
+impl Pipeline {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Runs every series through each transform in turn.
+    pub fn apply(&self, series: Vec<NamedSeries>) -> Vec<NamedSeries> {
+        self.0.iter().fold(series, |series, transform| {
+            series
+                .into_iter()
+                .flat_map(|s| transform.apply(s))
+                .collect()
+        })
+    }
+}
+
+impl FromStr for Transform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let window = |n: &str| match n.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!("'{}' is not a number of values", n)),
+        };
+        let bound = |b: &str| {
+            b.parse::<f64>()
+                .map_err(|_| format!("'{}' is not a number", b))
+        };
+
+        match words[..] {
+            ["delta"] => Ok(Transform::Delta),
+            ["rate"] => Ok(Transform::Rate),
+            ["avg", n] => Ok(Transform::MovingAverage(window(n)?)),
+            ["envelope", n] => Ok(Transform::Envelope(window(n)?)),
+            ["clamp", min, max] => {
+                let (min, max) = (bound(min)?, bound(max)?);
+                if min > max {
+                    return Err(format!(
+                        "clamp minimum {} is above its maximum {}",
+                        min, max
+                    ));
+                }
+                Ok(Transform::Clamp(min, max))
+            }
+            _ => Err(format!(
+                "'{}' is not one of delta, rate, avg N, envelope N or clamp MIN MAX",
+                s.trim()
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transform::Delta => write!(f, "delta"),
+            Transform::Rate => write!(f, "rate"),
+            Transform::MovingAverage(n) => write!(f, "avg {}", n),
+            Transform::Envelope(n) => write!(f, "envelope {}", n),
+            Transform::Clamp(min, max) => write!(f, "clamp {} {}", min, max),
+        }
+    }
+}
+
+impl FromStr for Pipeline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Pipeline::default());
+        }
+        s.split('|')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Pipeline)
+    }
+}
+
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let steps: Vec<String> = self.0.iter().map(|t| t.to_string()).collect();
+        write!(f, "{}", steps.join(" | "))
+    }
+}
+
+impl TryFrom<String> for Pipeline {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Pipeline> for String {
+    fn from(pipeline: Pipeline) -> Self {
+        pipeline.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(pipeline: &str, ys: &[f64]) -> Vec<NamedSeries> {
+        let points = ys
+            .iter()
+            .enumerate()
+            .map(|(i, y)| (i as f64 * 2.0, *y))
+            .collect();
+        pipeline
+            .parse::<Pipeline>()
+            .unwrap()
+            .apply(vec![("value".to_string(), points)])
+    }
+
+    fn values(series: &NamedSeries) -> Vec<f64> {
+        series.1.iter().map(|(_, y)| *y).collect()
+    }
+
+    #[test]
+    fn test_counter_rate_and_delta() {
+        let counter = [100.0, 110.0, 130.0, 130.0];
+        assert_eq!(values(&run("delta", &counter)[0]), vec![10.0, 20.0, 0.0]);
+        // Points are two seconds apart.
+        assert_eq!(values(&run("rate", &counter)[0]), vec![5.0, 10.0, 0.0]);
+    }
+
+    #[test]
+    fn test_chained_steps() {
+        let ys = [1.0, 3.0, 8.0, 2.0];
+        assert_eq!(values(&run("avg 2", &ys)[0]), vec![1.0, 2.0, 5.5, 5.0]);
+        assert_eq!(
+            values(&run("avg 2 | clamp 1.5 5", &ys)[0]),
+            vec![1.5, 2.0, 5.0, 5.0]
+        );
+
+        let envelope = run("envelope 2", &ys);
+        let names: Vec<&str> = envelope.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["value", "value min", "value max"]);
+        assert_eq!(values(&envelope[1]), vec![1.0, 1.0, 3.0, 2.0]);
+        assert_eq!(values(&envelope[2]), vec![1.0, 3.0, 8.0, 8.0]);
+    }
+
+    #[test]
+    fn test_parses_and_shows_pipeline() {
+        let pipeline: Pipeline = " rate |avg 5| clamp 0 100 ".parse().unwrap();
+        assert_eq!(pipeline.to_string(), "rate | avg 5 | clamp 0 100");
+        assert!("".parse::<Pipeline>().unwrap().is_empty());
+        assert!("avg 0".parse::<Pipeline>().is_err());
+        assert!("clamp 5 1".parse::<Pipeline>().is_err());
+        assert!("rate | sqrt".parse::<Pipeline>().is_err());
+    }
+}
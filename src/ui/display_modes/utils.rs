@@ -1,7 +1,57 @@
-use crate::command::Command;
+use ratatui::style::Style;
+use ratatui::text::{Line, Text};
 
-pub fn formatted_last_output(cmd: &Command) -> &str {
-    cmd.last_output()
-        .map(|c| c.output.as_str())
-        .unwrap_or("N/A")
+use crate::command::{Command, OutputStream};
+use crate::config::theme::Palette;
+use crate::ui::utils::AnsiText;
+
+/// The live or last output, styled only when it or how it's shown changed
+/// since the last render.
+pub fn formatted_last_output(cmd: &Command, p: &Palette) -> Text<'static> {
+    let live = cmd.live_output();
+    let key = (
+        cmd.output_generation,
+        live.is_some(),
+        cmd.output_view,
+        cmd.strip_ansi,
+    );
+    let mut formatted = cmd.formatted.borrow_mut();
+    if let Some((cached, text)) = formatted.as_ref() {
+        if *cached == key {
+            return text.clone();
+        }
+    }
+
+    let lines = match live {
+        Some(live) => Some(live.lines(cmd.output_view)),
+        None => cmd.last_output().map(|c| c.lines(cmd.output_view)),
+    };
+    let text = match lines {
+        Some(lines) => styled_lines(lines, !cmd.strip_ansi, p),
+        None => Text::styled("N/A", p.output),
+    };
+    *formatted = Some((key, text.clone()));
+    text
+}
+
+/// Output lines styled by the stream they came from.
+pub fn styled_lines(
+    lines: Vec<(OutputStream, &str)>,
+    keep_colors: bool,
+    p: &Palette,
+) -> Text<'static> {
+    // A style can be left open across lines, separately in each stream.
+    let (mut stdout_style, mut stderr_style) = (Style::default(), Style::default());
+    let lines: Vec<Line> = lines
+        .into_iter()
+        .flat_map(|(stream, line)| {
+            let (base, style) = match stream {
+                OutputStream::Stdout => (p.output, &mut stdout_style),
+                OutputStream::Stderr => (p.stderr, &mut stderr_style),
+            };
+            AnsiText::new_continued(line, keep_colors, style).to_lines(base)
+        })
+        .collect();
+
+    Text::from(lines)
 }
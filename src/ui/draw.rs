@@ -8,6 +8,7 @@ use crate::ui::display_select::draw_display_type_select;
 use crate::ui::help_menu::draw_help_menu;
 use crate::ui::observe;
 use crate::ui::panes;
+use crate::ui::prompt_input::draw_prompt_popup;
 use crate::ui::session_load::draw_session_list;
 use crate::ui::session_save::draw_session_save_popup;
 use crate::ui::status_line::draw_status_line;
@@ -43,6 +44,7 @@ pub fn draw_ui(app: &mut App, frame: &mut Frame) {
         AppMode::CmdEdit { .. } => draw_input_popup(frame, app),
         AppMode::SessionLoad { .. } => draw_session_list(frame, app),
         AppMode::SessionSave { .. } => draw_session_save_popup(frame, app),
+        AppMode::Prompt(_) => draw_prompt_popup(frame, app),
         AppMode::DisplayTypeSelect { .. } => draw_display_type_select(frame, app),
         AppMode::Help { .. } => draw_help_menu(frame, &app.config, &mut app.mode),
         _ => (),
@@ -13,7 +13,7 @@ use crate::controls::KeyMode;
 use crate::mode::AppMode;
 use crate::settings_line;
 use crate::ui::utils::centered_rect2;
-use crate::ui::utils::formatting::ToSettingsString;
+use crate::ui::utils::formatting::{format_alerts, ToSettingsString};
 use crate::ui::utils::scrollbar;
 
 pub fn build_keybinding_list<'a>(
@@ -76,16 +76,32 @@ pub fn draw_help_menu(frame: &mut Frame, c: &AppConfig, mode: &mut AppMode) {
         settings.extend(settings_line!(
             p,
             20,
+            "Alerts" => format_alerts(&c.alerts),
+            "Backoff" => c.backoff.map_or("None".to_string(), |b| b.to_string()),
             "Beep" => c.beep,
+            "Dedup History" => c.dedup_history,
             "Default Display" => format!("{:?}", c.default_display),
             "Exit on Change" => c.chg_exit,
+            "Exit on Change Pane" => c.chg_exit_pane.map_or("Any".to_string(), |id| id.to_string()),
+            "Exec Mode" => c.exec_mode,
             "Exit on Error" => c.err_exit,
+            "History Dir" => c.history_dir.display(),
+            "History Retention" => c.history_retention,
+            "Hooks" => c.hooks,
             "Interval" => format!("{:?}", c.interval),
             "Log Level" => c.log_level.as_deref().unwrap_or("None"),
             "Logs Dir" => c.logs_dir.display(),
+            "Max Concurrency" => c.max_concurrency.map_or("Unlimited".to_string(), |n| n.to_string()),
             "Max History" => c.max_history,
+            "Retry" => c.retry,
+            "Schedule Policy" => c.schedule_policy,
             "Sessions Dir" => c.sessions_dir.display(),
+            "Shell" => c.shell.as_deref().unwrap_or("$SHELL"),
             "Snapshot Dir" => c.snapshot_dir.display(),
+            "Store History" => c.store_history,
+            "Timeout" => c.timeout.map_or("None".to_string(), |t| format!("{:?}", t)),
+            "Timetable" => c.timetable.as_ref().map_or("Interval".to_string(), |t| t.to_string()),
+            "Working Dir" => c.cwd.as_ref().map_or("Current".to_string(), |d| d.display().to_string()),
             "Wrap" => c.wrap,
             "Zen" => c.zen,
         ));
@@ -99,6 +115,8 @@ pub fn draw_help_menu(frame: &mut Frame, c: &AppConfig, mode: &mut AppMode) {
         settings.extend(settings_line!(
             p,
             20,
+            "Chart Precision" => c.theme.chart_precision,
+            "Chart Time Labels" => c.theme.chart_time_labels,
             "Collapse Borders" => c.theme.collapse_borders,
             "Show State" => c.theme.show_state,
             "Show Last Update" => c.theme.show_last_updated,
@@ -6,9 +6,10 @@ pub mod draw;
 mod help_menu;
 mod observe;
 mod panes;
+mod prompt_input;
 mod session_load;
 mod session_save;
 mod status_line;
 pub mod utils;
 
-pub use self::display_modes::DisplayType;
+pub use self::display_modes::{ChartView, DisplayType, Pipeline, Series};
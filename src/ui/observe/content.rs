@@ -1,7 +1,11 @@
+use crate::command::{Command, CommandOutput};
 use crate::config::AppConfig;
 use crate::mode::DiffMode;
 use crate::ui::diffs;
-use crate::{command::Command, ui::utils::scrollbar};
+use crate::ui::utils::formatting::format_usage;
+use crate::ui::utils::scrollbar;
+use crate::ui::utils::AnsiText;
+use ratatui::text::Span;
 use ratatui::widgets::ScrollbarState;
 use ratatui::{
     layout::Rect,
@@ -14,7 +18,8 @@ pub fn render<'a>(
     area: Rect,
     config: &'a AppConfig,
     command: &'a Command,
-    selected_idx: usize,
+    current_output: Option<&CommandOutput>,
+    previous_output: Option<&CommandOutput>,
     diff_mode: DiffMode,
     search_query: &'a str,
     scroll_offset: u16,
@@ -23,21 +28,17 @@ pub fn render<'a>(
     is_focused: bool,
 ) {
     let p = &config.theme.palette;
-    let current_len = command.output_history.len();
-
-    let data_idx = current_len.saturating_sub(1).saturating_sub(selected_idx);
-    let prev_data_idx = data_idx.checked_sub(1);
-
-    let current_output = command.output_history.get(data_idx);
-    let previous_output = prev_data_idx.and_then(|idx| command.output_history.get(idx));
-
-    let current_text = current_output.map_or("", |c| &c.output);
-    let previous_text = previous_output.map_or("", |c| &c.output);
+    let current_text = current_output
+        .map(|c| AnsiText::new(&c.text(command.output_view), !command.strip_ansi))
+        .unwrap_or_default();
+    let previous_text = previous_output
+        .map(|c| AnsiText::new(&c.text(command.output_view), !command.strip_ansi))
+        .unwrap_or_default();
 
     let display_text = diffs::render_diff(
         &config.theme,
-        current_text,
-        previous_text,
+        &current_text,
+        &previous_text,
         diff_mode,
         search_query,
     );
@@ -48,10 +49,16 @@ pub fn render<'a>(
         p.border_inactive
     };
 
-    let content_block = Block::default()
+    let mut content_block = Block::default()
         .borders(Borders::ALL)
         .border_style(border_style)
         .padding(Padding::left(2));
+    if let Some(out) = current_output {
+        if let Some(usage) = &out.usage {
+            let summary = format!(" took {:?}, {} ", out.duration, format_usage(usage));
+            content_block = content_block.title_bottom(Span::styled(summary, p.meta_value));
+        }
+    }
 
     let inner_area = content_block.inner(area);
 
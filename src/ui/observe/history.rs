@@ -1,25 +1,62 @@
-use crate::command::Command;
+use chrono::NaiveDateTime;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
+
+use crate::command::CommandOutput;
 use crate::config::AppConfig;
 use crate::ui::utils::BlockExt;
-use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, List, ListItem};
 
-pub fn widget<'a>(config: &'a AppConfig, command: &'a Command, is_focused: bool) -> List<'a> {
+/// The label of a run: "Latest" for the newest, otherwise its time.
+pub fn time_label(time: Option<NaiveDateTime>, latest: bool) -> String {
+    match time {
+        _ if latest => "Latest".to_string(),
+        Some(time) => time.format("%H:%M:%S").to_string(),
+        None => String::new(),
+    }
+}
+
+/// The label of a history entry, which for unchanged runs merged into one
+/// says how many there were and when, e.g. `unchanged ×42 (10:01–10:43)`.
+pub fn label(out: &CommandOutput, latest: bool) -> String {
+    match out.first_seen {
+        Some(first_seen) if out.repeats > 1 => format!(
+            "unchanged ×{} ({}–{})",
+            out.repeats,
+            first_seen.format("%H:%M"),
+            out.time.format("%H:%M")
+        ),
+        _ => time_label(Some(out.time), latest),
+    }
+}
+
+/// Lists the `len` runs, newest first, with `label` giving the label of a
+/// run by its index from the oldest.
+///
+/// Only the runs that fit are listed, as a stored history can have days of
+/// them. `state` keeps the first one shown counted from the newest.
+#[allow(clippy::too_many_arguments)]
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    config: &AppConfig,
+    len: usize,
+    label: impl Fn(usize) -> String,
+    selected: usize,
+    state: &mut ListState,
+    is_focused: bool,
+) {
     let p = &config.theme.palette;
 
-    let items: Vec<ListItem> = command
-        .output_history
-        .iter()
-        .rev()
-        .enumerate()
-        .map(|(ui_idx, out)| {
-            let label = if ui_idx == 0 {
-                "Latest".to_string()
-            } else {
-                out.time.format("%H:%M:%S").to_string()
-            };
-            ListItem::new(label)
-        })
+    let rows = (area.height as usize).saturating_sub(2).max(1);
+    let mut first = state.offset();
+    if selected < first {
+        first = selected;
+    } else if selected >= first + rows {
+        first = selected + 1 - rows;
+    }
+
+    let items: Vec<ListItem> = (first..len.min(first + rows))
+        .map(|ui_idx| ListItem::new(label(len - 1 - ui_idx)))
         .collect();
 
     let border_style = if is_focused {
@@ -28,7 +65,7 @@ pub fn widget<'a>(config: &'a AppConfig, command: &'a Command, is_focused: bool)
         p.border_inactive
     };
 
-    List::new(items)
+    let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -40,5 +77,50 @@ pub fn widget<'a>(config: &'a AppConfig, command: &'a Command, is_focused: bool)
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
-        )
+        );
+
+    let mut shown = ListState::default().with_selected(Some(selected - first));
+    frame.render_stateful_widget(list, area, &mut shown);
+    *state = ListState::default()
+        .with_offset(first)
+        .with_selected(Some(selected));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn test_labels_merged_runs() {
+        let at = |minute| {
+            NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(10, minute, 0)
+                .unwrap()
+        };
+        let mut out = CommandOutput {
+            stdout: "a\n".to_string(),
+            stderr: String::new(),
+            order: Vec::new(),
+            time: at(1),
+            exit_status: Some(0),
+            duration: Duration::from_millis(1),
+            timed_out: false,
+            attempts: 1,
+            usage: None,
+            repeats: 1,
+            first_seen: None,
+        };
+        assert_eq!(label(&out, true), "Latest");
+        assert_eq!(label(&out, false), "10:01:00");
+
+        out.repeats = 42;
+        out.first_seen = Some(at(1));
+        out.time = at(43);
+        assert_eq!(label(&out, true), "unchanged ×42 (10:01–10:43)");
+    }
 }
@@ -1,5 +1,6 @@
 mod content;
 mod history;
+mod runtime;
 mod search;
 
 use std::collections::HashMap;
@@ -26,6 +27,11 @@ pub fn draw(
             .collapse_if(config.theme.collapse_borders)
             .areas(area);
 
+    let [history_area, runtime_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(5)])
+            .collapse_if(config.theme.collapse_borders)
+            .areas(history_area);
+
     let [search_area, content_area] = Layout::vertical([Constraint::Length(3), Constraint::Min(0)])
         .collapse_if(config.theme.collapse_borders)
         .areas(main_area);
@@ -43,13 +49,20 @@ pub fn draw(
         scroll_offset,
         max_scroll,
         scrollbar_state,
+        stored,
     } = mode_state
     {
         let Some(command) = commands.get(active_id) else {
             return;
         };
 
-        let current_len = command.output_history.len();
+        let current_len = match stored {
+            Some(stored) => {
+                stored.refresh();
+                stored.len()
+            }
+            None => command.output_history.len(),
+        };
         if current_len != *last_history_len {
             let diff = current_len.abs_diff(*last_history_len);
             if current_len > *last_history_len && *selected_history_idx > 0 {
@@ -61,9 +74,45 @@ pub fn draw(
         }
 
         // Render History
-        let history_w = history::widget(config, command, *focus == ObserveFocus::History);
-        history_list_state.select(Some(*selected_history_idx));
-        frame.render_stateful_widget(history_w, history_area, history_list_state);
+        let label = |idx: usize| {
+            let latest = idx + 1 == current_len;
+            match stored.as_ref() {
+                Some(stored) => history::time_label(stored.time(idx), latest),
+                None => command
+                    .output_history
+                    .get(idx)
+                    .map_or(String::new(), |out| history::label(out, latest)),
+            }
+        };
+        history::render(
+            frame,
+            history_area,
+            config,
+            current_len,
+            label,
+            *selected_history_idx,
+            history_list_state,
+            *focus == ObserveFocus::History,
+        );
+        runtime::render(frame, runtime_area, config, command);
+
+        let data_idx = current_len
+            .saturating_sub(1)
+            .saturating_sub(*selected_history_idx);
+        let prev_data_idx = data_idx.checked_sub(1);
+        let (current_output, previous_output) = match stored {
+            Some(stored) => {
+                stored.load(&[data_idx, prev_data_idx.unwrap_or(data_idx)]);
+                (
+                    stored.get(data_idx),
+                    prev_data_idx.and_then(|idx| stored.get(idx)),
+                )
+            }
+            None => (
+                command.output_history.get(data_idx),
+                prev_data_idx.and_then(|idx| command.output_history.get(idx)),
+            ),
+        };
 
         // Render Content
         content::render(
@@ -71,7 +120,8 @@ pub fn draw(
             content_area,
             config,
             command,
-            *selected_history_idx,
+            current_output,
+            previous_output,
             *diff_mode,
             search_input.value(),
             *scroll_offset,
@@ -0,0 +1,39 @@
+use ratatui::layout::Rect;
+use ratatui::widgets::{Block, Borders, Sparkline};
+use ratatui::Frame;
+
+use crate::command::Command;
+use crate::config::AppConfig;
+use crate::ui::display_modes::sparkline_data;
+use crate::ui::utils::BlockExt;
+
+/// How long each run in the history took, to spot a command getting slower.
+pub fn render(frame: &mut Frame, area: Rect, config: &AppConfig, command: &Command) {
+    let p = &config.theme.palette;
+
+    let title = match command.last_output() {
+        Some(out) => format!("Runtime {:?}", out.duration),
+        None => "Runtime".to_string(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(p.border_inactive)
+        .merge_if(config.theme.collapse_borders)
+        .title(title);
+
+    let data = sparkline_data(
+        command
+            .output_history
+            .iter()
+            .map(|out| out.duration.as_secs_f64()),
+    );
+    let max_data_points = block.inner(area).width as usize;
+    let display_data = &data[data.len().saturating_sub(max_data_points)..];
+
+    let sparkline = Sparkline::default()
+        .block(block)
+        .data(display_data)
+        .style(p.spark_line);
+
+    frame.render_widget(sparkline, area);
+}
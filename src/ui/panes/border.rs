@@ -10,7 +10,9 @@ pub fn create_pane_block<'a>(config: &AppConfig, ni: NodeInfo<'a>) -> Block<'a>
 
     let mut block = Block::default()
         .borders(Borders::ALL)
-        .border_style(if ni.is_active {
+        .border_style(if ni.flashing {
+            p.border_alert
+        } else if ni.is_active {
             p.border_active
         } else {
             p.border_inactive
@@ -19,12 +21,28 @@ pub fn create_pane_block<'a>(config: &AppConfig, ni: NodeInfo<'a>) -> Block<'a>
         .merge_if(config.theme.collapse_borders && config.zen);
 
     if !config.zen {
-        let title_top_left = Line::from(vec![
-            Span::styled("Every ", p.meta_label),
-            Span::styled(ni.interval_secs_str, p.meta_value),
-            Span::styled(": ", p.meta_label),
-            Span::styled(ni.exec_str, p.meta_highlight),
-        ]);
+        let mut spans = if ni.timetable_str.is_empty() {
+            vec![
+                Span::styled("Every ", p.meta_label),
+                Span::styled(ni.interval_secs_str, p.meta_value),
+            ]
+        } else {
+            vec![
+                Span::styled("At ", p.meta_label),
+                Span::styled(ni.timetable_str, p.meta_value),
+                Span::styled(" (", p.meta_label),
+                Span::styled(ni.next_run_str, p.meta_value),
+                Span::styled(")", p.meta_label),
+            ]
+        };
+        if !ni.schedule_policy_str.is_empty() {
+            spans.push(Span::styled(" (", p.meta_label));
+            spans.push(Span::styled(ni.schedule_policy_str, p.meta_value));
+            spans.push(Span::styled(")", p.meta_label));
+        }
+        spans.push(Span::styled(": ", p.meta_label));
+        spans.push(Span::styled(ni.exec_str, p.meta_highlight));
+        let title_top_left = Line::from(spans);
         block = block.title(title_top_left);
 
         if config.theme.show_state {
@@ -34,11 +52,26 @@ pub fn create_pane_block<'a>(config: &AppConfig, ni: NodeInfo<'a>) -> Block<'a>
         }
 
         if config.theme.show_last_updated {
-            let title_bottom_left = Line::from(vec![
+            let duration = if ni.timed_out {
+                Span::styled(format!(" (timed out after {})", ni.duration), p.error)
+            } else {
+                Span::styled(format!(" ({})", ni.duration), p.meta_value)
+            };
+            let mut spans = vec![
                 Span::styled("Updated: ", p.meta_label),
                 Span::styled(ni.last_exec_time, p.meta_value),
-                Span::styled(format!(" ({})", ni.duration), p.meta_value),
-            ]);
+                duration,
+            ];
+            if let Some(code) = ni.exit_status {
+                spans.push(Span::styled(format!(" exit {}", code), p.error));
+            }
+            if ni.attempts > 1 {
+                spans.push(Span::styled(
+                    format!(" after {} attempts", ni.attempts),
+                    p.meta_value,
+                ));
+            }
+            let title_bottom_left = Line::from(spans);
             block = block.title_bottom(title_bottom_left);
         }
 
@@ -49,8 +82,9 @@ pub fn create_pane_block<'a>(config: &AppConfig, ni: NodeInfo<'a>) -> Block<'a>
         }
 
         if config.theme.show_history_meter {
+            let meter_style = if ni.timed_out { p.error } else { p.meta_meter };
             let title_bottom_centered =
-                Line::from(Span::styled(ni.history_limit, p.meta_meter)).left_aligned();
+                Line::from(Span::styled(ni.history_limit, meter_style)).left_aligned();
             block = block.title_bottom(title_bottom_centered);
         }
     }
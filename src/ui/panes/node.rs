@@ -4,7 +4,7 @@ use ratatui::layout::{Constraint, Layout};
 use ratatui::prelude::{Frame, Rect};
 use ratatui::widgets::Paragraph;
 
-use crate::command::Command;
+use crate::command::{Command, TerminalSize};
 use crate::config::AppConfig;
 use crate::pane::{PaneKey, PaneManager, PaneNodeData};
 use crate::ui::display_modes::render_command_output;
@@ -35,6 +35,12 @@ pub fn draw_recursive(
 
                 let block = create_pane_block(config, node_info);
 
+                let inner = block.inner(area);
+                cmd.resize_terminal(TerminalSize {
+                    cols: inner.width,
+                    rows: inner.height,
+                });
+
                 render_command_output(frame, area, config, cmd, block);
             } else {
                 let block = create_pane_block(config, NodeInfo::no_command(is_active));
@@ -1,20 +1,34 @@
 use std::borrow::Cow;
+use std::time::{Duration, Instant};
 
+use chrono::Local;
 use humantime::format_duration;
 
 use crate::{
-    command::Command, config::AppConfig, ui::panes::history_meter::generate_history_meter_string,
+    command::{Command, ExecMode, OutputView},
+    config::AppConfig,
+    ui::panes::history_meter::generate_history_meter_string,
+    ui::{ChartView, Series},
 };
 
 pub struct NodeInfo<'a> {
     pub is_active: bool,
+    /// An alert is flashing the border, and it's lit right now.
+    pub flashing: bool,
     pub exec_str: Cow<'a, str>,
     pub interval_secs_str: Cow<'a, str>,
+    pub schedule_policy_str: Cow<'a, str>,
+    /// Empty unless the pane runs on a timetable.
+    pub timetable_str: Cow<'a, str>,
+    pub next_run_str: Cow<'a, str>,
     pub last_exec_time: Cow<'a, str>,
     pub duration: Cow<'a, str>,
     pub state_str: Cow<'a, str>,
     pub display_type_str: Cow<'a, str>,
     pub history_limit: Cow<'a, str>,
+    pub timed_out: bool,
+    pub exit_status: Option<i32>,
+    pub attempts: u32,
 }
 
 impl<'a> NodeInfo<'a> {
@@ -29,30 +43,128 @@ impl<'a> NodeInfo<'a> {
             .map(|out| format_duration(out.duration).to_string()) // Assuming this returns String
             .unwrap_or_else(|| "N/A".to_string());
 
+        let timed_out = c.last_output().is_some_and(|out| out.timed_out);
+
+        let exit_status = c
+            .last_output()
+            .and_then(|out| out.exit_status)
+            .filter(|code| *code != 0);
+
+        let (timetable_str, next_run_str) = match &c.timetable {
+            Some(timetable) => {
+                let now = Local::now();
+                let next_run = timetable
+                    .next_after(now)
+                    .map(|next| {
+                        let secs = (next - now).num_milliseconds().max(0) as u64 / 1000;
+                        format!("next in {}", format_duration(Duration::from_secs(secs)))
+                    })
+                    .unwrap_or_else(|| "no next run".to_string());
+                (timetable.to_string(), next_run)
+            }
+            None => (String::new(), String::new()),
+        };
+
+        let interval_str = match c.backed_off_interval {
+            Some(backed_off) => format!("{:?} → {:?}", c.interval, backed_off),
+            None => format!("{:?}", c.interval),
+        };
+
+        let attempts = c.last_output().map_or(1, |out| out.attempts);
+
+        // Lit for the first half of every second until the flash ends.
+        let flashing = c.flash_until.is_some_and(|until| {
+            let now = Instant::now();
+            now < until && (until - now).subsec_millis() >= 500
+        });
+
+        let mut tags = Vec::new();
+        if c.output_view != OutputView::Both {
+            tags.push(c.output_view.to_string());
+        }
+        if c.exec_mode != ExecMode::Shell {
+            tags.push(c.exec_mode.to_string());
+        }
+        if c.pty {
+            tags.push("pty".to_string());
+        }
+        if c.series != Series::Value {
+            tags.push(c.series.to_string());
+        }
+        if let Some(extractor) = &c.extractor {
+            tags.push(format!("extract {}", extractor));
+        }
+        if !c.transforms.is_empty() {
+            tags.push(c.transforms.to_string());
+        }
+        if c.secondary_axis {
+            tags.push("y2".to_string());
+        }
+        if c.chart_view != ChartView::default() {
+            let window = c.chart_view.window(c.output_history.len(), c.chart_fit());
+            tags.push(format!(
+                "runs {}-{} of {}",
+                window.start + 1,
+                window.end,
+                c.output_history.len()
+            ));
+        }
+        if !c.alerts.is_empty() {
+            tags.push(format!("alerts {}", c.alerts.len()));
+        }
+        if !c.hooks.is_empty() {
+            tags.push("hooks".to_string());
+        }
+        if c.backoff.is_some() {
+            tags.push("backoff".to_string());
+        }
+        if c.dedup_history != config.dedup_history {
+            tags.push(if c.dedup_history { "dedup" } else { "no dedup" }.to_string());
+        }
+        let display_type_str = if tags.is_empty() {
+            format!("{:?}", c.display_type)
+        } else {
+            format!("{:?} [{}]", c.display_type, tags.join(", "))
+        };
+
         let meter = generate_history_meter_string(c.output_history.len(), config.max_history);
 
         NodeInfo {
             is_active,
+            flashing,
             exec_str: Cow::Borrowed(&c.exec),
-            interval_secs_str: Cow::Owned(format!("{:?}", c.interval)),
+            interval_secs_str: Cow::Owned(interval_str),
+            schedule_policy_str: Cow::Owned(c.schedule_policy.to_string()),
+            timetable_str: Cow::Owned(timetable_str),
+            next_run_str: Cow::Owned(next_run_str),
             last_exec_time: Cow::Owned(last_exec_time),
             duration: Cow::Owned(duration),
             state_str: Cow::Owned(c.state.to_string()),
-            display_type_str: Cow::Owned(format!("{:?}", c.display_type)),
+            display_type_str: Cow::Owned(display_type_str),
             history_limit: Cow::Owned(meter),
+            timed_out,
+            exit_status,
+            attempts,
         }
     }
 
     pub fn no_command(is_active: bool) -> Self {
         Self {
             is_active: is_active,
+            flashing: false,
             exec_str: Cow::Borrowed("N/A"),
             interval_secs_str: Cow::Borrowed("0s"),
+            schedule_policy_str: Cow::Borrowed(""),
+            timetable_str: Cow::Borrowed(""),
+            next_run_str: Cow::Borrowed(""),
             last_exec_time: Cow::Borrowed("N/A"),
             duration: Cow::Borrowed("0s"),
             state_str: Cow::Borrowed("N/A"),
             display_type_str: Cow::Borrowed("N/A"),
             history_limit: Cow::Borrowed(""),
+            timed_out: false,
+            exit_status: None,
+            attempts: 1,
         }
     }
 }
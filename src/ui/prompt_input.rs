@@ -0,0 +1,31 @@
+use ratatui::layout::Position;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+use super::utils::centered_rect;
+use crate::mode::AppMode;
+use crate::App;
+
+pub fn draw_prompt_popup(frame: &mut Frame, app: &App) {
+    if let AppMode::Prompt(prompt) = &app.mode {
+        let popup_area = centered_rect(60, frame.area(), 3);
+
+        Clear.render(popup_area, frame.buffer_mut());
+
+        frame.set_cursor_position(Position::new(
+            popup_area.x + 1 + prompt.input.cursor() as u16,
+            popup_area.y + 1,
+        ));
+
+        let mut block = Block::default().borders(Borders::ALL).title(prompt.title);
+        if let Some(error) = &prompt.error {
+            let p = &app.config.theme.palette;
+            block = block.title_bottom(Line::from(Span::styled(error.as_str(), p.error)));
+        }
+
+        let input_widget = Paragraph::new(prompt.input.value()).block(block);
+
+        frame.render_widget(input_widget, popup_area);
+    }
+}
@@ -1,9 +1,10 @@
 use ratatui::layout::{Alignment, Constraint, Layout, Rect};
-use ratatui::text::Line;
+use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
 
-use crate::app::App;
+use crate::app::{App, BANNER_DURATION};
+use crate::command::ExecPool;
 use crate::mode::AppMode;
 
 pub fn mode_output(mode: &AppMode) -> String {
@@ -15,16 +16,39 @@ pub fn mode_output(mode: &AppMode) -> String {
     }
 }
 
+/// Commands running and waiting for a slot in the executor pool.
+pub fn pool_output(pool: &ExecPool) -> String {
+    let running = match pool.limit() {
+        Some(limit) => format!("{}/{}", pool.running(), limit),
+        None => pool.running().to_string(),
+    };
+    format!("running {} queued {} ", running, pool.queued())
+}
+
 pub fn draw_status_line(frame: &mut Frame, area: Rect, app: &App) {
-    let [left_area, right_area] =
-        Layout::horizontal([Constraint::Min(0), Constraint::Length(25)]).areas(area);
+    let pool_content = pool_output(&app.pool);
+    let [left_area, pool_area, right_area] = Layout::horizontal([
+        Constraint::Min(0),
+        Constraint::Length(pool_content.len() as u16),
+        Constraint::Length(25),
+    ])
+    .areas(area);
 
-    let left_content = Line::from(mode_output(&app.mode));
+    let mut left_content = Line::from(mode_output(&app.mode));
+    if let Some((banner, raised)) = &app.banner {
+        if raised.elapsed() < BANNER_DURATION {
+            let style = app.config.theme.palette.error;
+            left_content.push_span(Span::styled(format!("  alert: {}", banner), style));
+        }
+    }
     let left_widget = Paragraph::new(left_content);
 
+    let pool_widget = Paragraph::new(Line::from(pool_content)).alignment(Alignment::Right);
+
     let right_content = Line::from("?-Help q/esc - Quit/Back ");
     let right_widget = Paragraph::new(right_content).alignment(Alignment::Right);
 
     frame.render_widget(left_widget, left_area);
+    frame.render_widget(pool_widget, pool_area);
     frame.render_widget(right_widget, right_area);
 }
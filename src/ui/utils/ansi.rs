@@ -0,0 +1,266 @@
+use ratatui::prelude::*;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Command output with its escape sequences removed, remembering which SGR
+/// style was active for each part of the remaining text.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AnsiText {
+    pub plain: String,
+    /// Byte offsets into `plain` where a new style starts, in ascending order.
+    runs: Vec<(usize, Style)>,
+}
+
+impl AnsiText {
+    pub fn parse(input: &str) -> Self {
+        Self::parse_continued(input, &mut Style::default())
+    }
+
+    /// Parses `input` starting in `style`, the style left active by the text
+    /// before it, and leaves the style active at its end in `style`.
+    pub fn parse_continued(input: &str, style: &mut Style) -> Self {
+        let mut plain = String::with_capacity(input.len());
+        let mut runs: Vec<(usize, Style)> = Vec::new();
+        if *style != Style::default() {
+            runs.push((0, *style));
+        }
+        let mut chars = input.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != ESC {
+                plain.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                // CSI: parameters up to a final byte in 0x40..=0x7e.
+                Some('[') => {
+                    let mut params = String::new();
+                    let mut last = None;
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            last = Some(c);
+                            break;
+                        }
+                        params.push(c);
+                    }
+
+                    if last == Some('m') {
+                        *style = apply_sgr(*style, &params);
+                        match runs.last_mut() {
+                            Some((offset, s)) if *offset == plain.len() => *s = *style,
+                            _ => runs.push((plain.len(), *style)),
+                        }
+                    }
+                }
+                // OSC: terminated by BEL or ST (ESC \).
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == BEL {
+                            break;
+                        }
+                        if c == ESC && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // nF: intermediate bytes in 0x20..=0x2f up to a final byte,
+                // e.g. `ESC ( B` from `tput sgr0`.
+                Some('\x20'..='\x2f') => {
+                    for c in chars.by_ref() {
+                        if !('\x20'..='\x2f').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // Fp, Fe and Fs escapes are the one byte after ESC, e.g.
+                // `ESC 7` or `ESC =`.
+                _ => {}
+            }
+        }
+
+        AnsiText { plain, runs }
+    }
+
+    /// Parses `input`, dropping the styles unless `keep_colors` is set.
+    pub fn new(input: &str, keep_colors: bool) -> Self {
+        Self::parse(input).keep_colors(keep_colors)
+    }
+
+    /// Like [`AnsiText::new`], for a line that picks up in `style` as in
+    /// [`AnsiText::parse_continued`].
+    pub fn new_continued(input: &str, keep_colors: bool, style: &mut Style) -> Self {
+        Self::parse_continued(input, style).keep_colors(keep_colors)
+    }
+
+    fn keep_colors(mut self, keep_colors: bool) -> Self {
+        if !keep_colors {
+            self.runs.clear();
+        }
+        self
+    }
+
+    /// Splits `part`, which starts at byte `offset` of `plain`, at every style
+    /// change and pairs each piece with the style active there.
+    pub fn styled_pieces<'a>(&self, part: &'a str, offset: usize) -> Vec<(&'a str, Style)> {
+        let end = offset + part.len();
+        let first = self.runs.partition_point(|(start, _)| *start <= offset);
+        let mut style = first
+            .checked_sub(1)
+            .map(|i| self.runs[i].1)
+            .unwrap_or_default();
+
+        let mut pieces = Vec::new();
+        let mut from = offset;
+        for (start, next) in self.runs[first..].iter().take_while(|(s, _)| *s < end) {
+            if *start > from {
+                pieces.push((&part[from - offset..start - offset], style));
+            }
+            from = *start;
+            style = *next;
+        }
+        if from < end {
+            pieces.push((&part[from - offset..], style));
+        }
+
+        pieces
+    }
+
+    /// The text as lines of spans, with the output colors patched over `base`.
+    pub fn to_lines(&self, base: Style) -> Vec<Line<'static>> {
+        let mut offset = 0;
+        self.plain
+            .split_inclusive('\n')
+            .map(|raw| {
+                let line = raw.trim_end_matches(['\n', '\r']);
+                let spans: Vec<Span> = self
+                    .styled_pieces(line, offset)
+                    .into_iter()
+                    .map(|(piece, style)| Span::styled(piece.to_string(), base.patch(style)))
+                    .collect();
+                offset += raw.len();
+                Line::from(spans)
+            })
+            .collect()
+    }
+}
+
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let mut codes = params.split(';').map(|p| p.parse::<u8>().unwrap_or(0));
+
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            6 => style.add_modifier(Modifier::RAPID_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            8 => style.add_modifier(Modifier::HIDDEN),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => clear_modifier(style, Modifier::BOLD | Modifier::DIM),
+            23 => clear_modifier(style, Modifier::ITALIC),
+            24 => clear_modifier(style, Modifier::UNDERLINED),
+            25 => clear_modifier(style, Modifier::SLOW_BLINK | Modifier::RAPID_BLINK),
+            27 => clear_modifier(style, Modifier::REVERSED),
+            28 => clear_modifier(style, Modifier::HIDDEN),
+            29 => clear_modifier(style, Modifier::CROSSED_OUT),
+            30..=37 => style.fg(Color::Indexed(code - 30)),
+            38 => match extended_color(&mut codes) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            39 => Style { fg: None, ..style },
+            40..=47 => style.bg(Color::Indexed(code - 40)),
+            48 => match extended_color(&mut codes) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            49 => Style { bg: None, ..style },
+            90..=97 => style.fg(Color::Indexed(code - 90 + 8)),
+            100..=107 => style.bg(Color::Indexed(code - 100 + 8)),
+            _ => style,
+        };
+    }
+
+    style
+}
+
+fn clear_modifier(mut style: Style, modifier: Modifier) -> Style {
+    style.add_modifier.remove(modifier);
+    style
+}
+
+/// Reads the `5;n` or `2;r;g;b` tail of a 38/48 sequence.
+fn extended_color(codes: &mut impl Iterator<Item = u8>) -> Option<Color> {
+    match codes.next()? {
+        5 => Some(Color::Indexed(codes.next()?)),
+        2 => Some(Color::Rgb(codes.next()?, codes.next()?, codes.next()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_strips_sequences_and_keeps_styles() {
+        let text = AnsiText::parse("a\x1b[31mred\x1b[0m b\x1b]0;title\x07\x1b[2K");
+
+        assert_eq!(text.plain, "ared b");
+        assert_eq!(
+            text.styled_pieces(&text.plain, 0),
+            vec![
+                ("a", Style::default()),
+                ("red", Style::default().fg(Color::Indexed(1))),
+                (" b", Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_styled_pieces_of_a_slice() {
+        let text = AnsiText::parse("\x1b[1;38;5;208mhello\x1b[22m world");
+        let orange = Style::default().fg(Color::Indexed(208));
+
+        assert_eq!(
+            text.styled_pieces(&text.plain[3..8], 3),
+            vec![("lo", orange.add_modifier(Modifier::BOLD)), (" wo", orange)]
+        );
+    }
+
+    #[test]
+    fn test_parse_skips_other_escapes() {
+        let text = AnsiText::parse("\x1b[1mbold\x1b(B\x1b[m plain\x1b7\x1b=\x1b8");
+
+        assert_eq!(text.plain, "bold plain");
+    }
+
+    #[test]
+    fn test_parse_continues_style_across_lines() {
+        let red = Style::default().fg(Color::Indexed(1));
+        let mut style = Style::default();
+
+        AnsiText::parse_continued("\x1b[31mone", &mut style);
+        let next = AnsiText::parse_continued("two\x1b[0m three", &mut style);
+
+        assert_eq!(
+            next.styled_pieces(&next.plain, 0),
+            vec![("two", red), (" three", Style::default())]
+        );
+        assert_eq!(style, Style::default());
+    }
+
+    #[test]
+    fn test_new_without_colors() {
+        let text = AnsiText::new("\x1b[32mok\x1b[0m", false);
+
+        assert_eq!(text.plain, "ok");
+        assert_eq!(text.styled_pieces("ok", 0), vec![("ok", Style::default())]);
+    }
+}
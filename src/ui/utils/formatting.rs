@@ -1,4 +1,9 @@
 use std::fmt::Display;
+use std::time::Duration;
+
+use crate::alert::AlertRule;
+use crate::command::ResourceUsage;
+
 pub trait ToSettingsString {
     fn to_settings_string(&self) -> String;
 }
@@ -9,6 +14,86 @@ impl<T: Display> ToSettingsString for T {
     }
 }
 
+/// Formats a byte count with binary units, e.g. `1.5 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", value, UNITS[unit]),
+    }
+}
+
+/// Formats seconds the way durations are written elsewhere, e.g. `250ms` or
+/// `1m30s`, rounded to keep labels short.
+pub fn format_seconds(secs: f64) -> String {
+    let Ok(duration) = Duration::try_from_secs_f64(secs) else {
+        return secs.to_string();
+    };
+    let rounded = match duration.as_millis() {
+        0 => Duration::from_micros(duration.as_micros() as u64),
+        1..1000 => Duration::from_millis(duration.as_millis() as u64),
+        _ => Duration::from_secs(duration.as_secs()),
+    };
+
+    humantime::format_duration(rounded)
+        .to_string()
+        .replace(' ', "")
+}
+
+/// Formats a plain number with an SI prefix and at most `precision`
+/// decimals, e.g. `1.25k` or `3.5M`.
+pub fn format_si(value: f64, precision: usize) -> String {
+    const PREFIXES: [&str; 5] = ["", "k", "M", "G", "T"];
+
+    let mut scaled = value;
+    let mut prefix = 0;
+    while scaled.abs() >= 1000.0 && prefix < PREFIXES.len() - 1 {
+        scaled /= 1000.0;
+        prefix += 1;
+    }
+
+    let mut number = format!("{:.*}", precision, scaled);
+    if number.contains('.') {
+        number = number
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string();
+    }
+    if number == "-0" {
+        number = "0".to_string();
+    }
+    format!("{}{}", number, PREFIXES[prefix])
+}
+
+pub fn format_usage(usage: &ResourceUsage) -> String {
+    format!(
+        "cpu {:?} ({:?} user, {:?} sys), max rss {}, stdout {}, stderr {}",
+        usage.cpu_time(),
+        usage.user_time,
+        usage.system_time,
+        format_bytes(usage.max_rss),
+        format_bytes(usage.stdout_bytes),
+        format_bytes(usage.stderr_bytes)
+    )
+}
+
+/// Alert rules separated by `; `, or `N/A` without any.
+pub fn format_alerts(alerts: &[AlertRule]) -> String {
+    if alerts.is_empty() {
+        return "N/A".to_string();
+    }
+    let rules: Vec<String> = alerts.iter().map(|rule| rule.to_string()).collect();
+    rules.join("; ")
+}
+
 #[macro_export]
 macro_rules! settings_line {
     ($p:expr, $width:expr, $($label:expr => $value:expr),* $(,)?) => {
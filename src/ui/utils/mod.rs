@@ -1,9 +1,11 @@
+mod ansi;
 mod area;
 mod components;
 mod ext;
 pub mod formatting;
 mod search;
 
+pub use ansi::*;
 pub use area::*;
 pub use components::*;
 pub use ext::*;